@@ -28,6 +28,7 @@ use swc_common::Loc;
 use swc_common::SourceFile;
 use swc_common::SourceMap;
 use swc_common::Span;
+use swc_ecmascript::ast;
 use swc_ecmascript::ast::Module;
 use swc_ecmascript::ast::Program;
 use swc_ecmascript::codegen::text_writer::JsWriter;
@@ -48,6 +49,7 @@ use swc_ecmascript::transforms::pass::Optional;
 use swc_ecmascript::transforms::proposals;
 use swc_ecmascript::transforms::react;
 use swc_ecmascript::transforms::typescript;
+use swc_ecmascript::visit::Fold;
 use swc_ecmascript::visit::FoldWith;
 
 static TARGET: JscTarget = JscTarget::Es2020;
@@ -202,6 +204,9 @@ pub struct EmitOptions {
   pub jsx_fragment_factory: String,
   /// Should JSX be transformed or preserved.  Defaults to `true`.
   pub transform_jsx: bool,
+  /// Should static imports be rewritten into awaited dynamic imports, so the
+  /// code can be evaluated as a script by the REPL.  Defaults to `false`.
+  pub repl_imports: bool,
 }
 
 impl Default for EmitOptions {
@@ -213,6 +218,7 @@ impl Default for EmitOptions {
       jsx_factory: "React.createElement".into(),
       jsx_fragment_factory: "React.Fragment".into(),
       transform_jsx: true,
+      repl_imports: false,
     }
   }
 }
@@ -228,6 +234,7 @@ impl From<tsc_config::TsConfig> for EmitOptions {
       jsx_factory: options.jsx_factory,
       jsx_fragment_factory: options.jsx_fragment_factory,
       transform_jsx: options.jsx == "react",
+      repl_imports: false,
     }
   }
 }
@@ -265,6 +272,16 @@ impl ParsedModule {
     self.leading_comments.clone()
   }
 
+  /// Returns `true` if the module contains import or export declarations,
+  /// meaning it can't be evaluated as a script.
+  pub fn has_module_declarations(&self) -> bool {
+    self
+      .module
+      .body
+      .iter()
+      .any(|item| matches!(item, ast::ModuleItem::ModuleDecl(_)))
+  }
+
   /// Get a location for a given span within the module.
   pub fn get_location(&self, span: &Span) -> Location {
     self.source_map.lookup_char_pos(span.lo).into()
@@ -294,6 +311,9 @@ impl ParsedModule {
     );
     let mut passes = chain!(
       Optional::new(jsx_pass, options.transform_jsx),
+      // this has to run before the types are stripped, as imports which are
+      // not referenced by the rest of the input would otherwise be removed.
+      Optional::new(ReplImportRewriter, options.repl_imports),
       proposals::decorators::decorators(proposals::decorators::Config {
         legacy: true,
         emit_metadata: options.emit_metadata
@@ -349,6 +369,131 @@ impl ParsedModule {
   }
 }
 
+/// Rewrites the static imports of a REPL input into awaited dynamic imports,
+/// which are resolved by the module loader of the REPL worker.
+///
+/// `import a, { b as c } from "./mod.ts"` becomes
+/// `const { default: a, b: c } = await import("./mod.ts")`.
+struct ReplImportRewriter;
+
+impl ReplImportRewriter {
+  fn rewrite(&self, decl: ast::ImportDecl) -> ast::Stmt {
+    let span = decl.span;
+    let import_call = ast::Expr::Await(ast::AwaitExpr {
+      span,
+      arg: Box::new(ast::Expr::Call(ast::CallExpr {
+        span,
+        callee: ast::ExprOrSuper::Expr(Box::new(ast::Expr::Ident(
+          ast::Ident::new("import".into(), span),
+        ))),
+        args: vec![ast::ExprOrSpread {
+          spread: None,
+          expr: Box::new(ast::Expr::Lit(ast::Lit::Str(decl.src))),
+        }],
+        type_args: None,
+      })),
+    });
+
+    let mut maybe_namespace = None;
+    let mut props = Vec::new();
+    for specifier in decl.specifiers {
+      match specifier {
+        ast::ImportSpecifier::Namespace(specifier) => {
+          maybe_namespace = Some(specifier.local);
+        }
+        ast::ImportSpecifier::Default(specifier) => {
+          props.push(ast::ObjectPatProp::KeyValue(ast::KeyValuePatProp {
+            key: ast::PropName::Ident(ast::Ident::new(
+              "default".into(),
+              specifier.span,
+            )),
+            value: Box::new(ast::Pat::Ident(specifier.local)),
+          }));
+        }
+        ast::ImportSpecifier::Named(specifier) => match specifier.imported {
+          Some(imported) => {
+            props.push(ast::ObjectPatProp::KeyValue(ast::KeyValuePatProp {
+              key: ast::PropName::Ident(imported),
+              value: Box::new(ast::Pat::Ident(specifier.local)),
+            }));
+          }
+          None => {
+            props.push(ast::ObjectPatProp::Assign(ast::AssignPatProp {
+              span: specifier.span,
+              key: specifier.local,
+              value: None,
+            }));
+          }
+        },
+      }
+    }
+
+    if maybe_namespace.is_none() && props.is_empty() {
+      return ast::Stmt::Expr(ast::ExprStmt {
+        span,
+        expr: Box::new(import_call),
+      });
+    }
+
+    let mut decls = Vec::new();
+    // With a namespace import, the other specifiers are destructured from the
+    // namespace instead of importing the module a second time.
+    let props_init = match maybe_namespace {
+      Some(namespace) => {
+        decls.push(ast::VarDeclarator {
+          span,
+          name: ast::Pat::Ident(namespace.clone()),
+          init: Some(Box::new(import_call)),
+          definite: false,
+        });
+        ast::Expr::Ident(namespace)
+      }
+      None => import_call,
+    };
+    if !props.is_empty() {
+      decls.push(ast::VarDeclarator {
+        span,
+        name: ast::Pat::Object(ast::ObjectPat {
+          span,
+          props,
+          optional: false,
+          type_ann: None,
+        }),
+        init: Some(Box::new(props_init)),
+        definite: false,
+      });
+    }
+
+    ast::Stmt::Decl(ast::Decl::Var(ast::VarDecl {
+      span,
+      kind: ast::VarDeclKind::Const,
+      declare: false,
+      decls,
+    }))
+  }
+}
+
+impl Fold for ReplImportRewriter {
+  fn fold_module_items(
+    &mut self,
+    items: Vec<ast::ModuleItem>,
+  ) -> Vec<ast::ModuleItem> {
+    items
+      .into_iter()
+      .filter_map(|item| match item {
+        ast::ModuleItem::ModuleDecl(ast::ModuleDecl::Import(decl)) => {
+          if decl.type_only {
+            None
+          } else {
+            Some(ast::ModuleItem::Stmt(self.rewrite(decl)))
+          }
+        }
+        item => Some(item),
+      })
+      .collect()
+  }
+}
+
 pub fn parse_with_source_map(
   specifier: &str,
   source: &str,
@@ -706,4 +851,36 @@ mod tests {
       .expect("could not strip types");
     assert!(code.contains("_applyDecoratedDescriptor("));
   }
+
+  #[test]
+  fn test_transpile_repl_imports() {
+    let source = r#"
+    import type { A } from "./a.ts";
+    import "./b.ts";
+    import c, { d, e as f } from "./c.ts";
+    import * as g from "./g.ts";
+    import h, * as i from "./h.ts";
+    const j: number = 1;
+    "#;
+    let module = parse("$deno$repl.ts", source, &MediaType::TypeScript)
+      .expect("could not parse module");
+    let (code, maybe_map) = module
+      .transpile(&EmitOptions {
+        inline_source_map: false,
+        repl_imports: true,
+        ..EmitOptions::default()
+      })
+      .expect("could not strip types");
+    assert!(!code.contains("./a.ts"));
+    assert!(code.contains("await import(\"./b.ts\");"));
+    assert!(code.contains("default: c"));
+    assert!(code.contains("e: f"));
+    assert!(code.contains("= await import(\"./c.ts\");"));
+    assert!(code.contains("const g = await import(\"./g.ts\");"));
+    assert!(code.contains("const i = await import(\"./h.ts\"), {"));
+    assert!(code.contains("default: h"));
+    assert!(code.contains("const j = 1;"));
+    assert!(!code.contains("import \""));
+    assert!(maybe_map.is_some());
+  }
 }
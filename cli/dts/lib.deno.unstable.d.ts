@@ -880,14 +880,87 @@ declare namespace Deno {
     close(): void;
     /** Return the address of the `UDPConn`. */
     readonly addr: Addr;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Join the IPv4 multicast group `address` on the interface with the
+     * address `networkInterface`, or on the default interface if it's not
+     * given. Only supported for `"udp"` sockets.
+     *
+     * Requires `allow-net` permission for the group address. */
+    joinMulticastV4(address: string, networkInterface?: string): void;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Join the IPv6 multicast group `address` on the interface with the index
+     * `networkInterface`, or on the default interface if it's not given. Only
+     * supported for `"udp"` sockets.
+     *
+     * Requires `allow-net` permission for the group address. */
+    joinMulticastV6(address: string, networkInterface?: number): void;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Leave a multicast group joined with `joinMulticastV4` or
+     * `joinMulticastV6`, passing the same interface.
+     *
+     * Requires `allow-net` permission for the group address. */
+    leaveMulticast(address: string, networkInterface?: string | number): void;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Set whether multicast messages sent by the socket are looped back to
+     * the local host. Enabled by default. */
+    setMulticastLoopback(loopback?: boolean): void;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Set the time-to-live (the hop limit for IPv6 sockets) of outgoing
+     * multicast messages. Defaults to 1, which keeps them in the local
+     * network. */
+    setMulticastTTL(ttl: number): void;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Set whether the socket may send messages to broadcast addresses. */
+    setBroadcast(broadcast?: boolean): void;
     [Symbol.asyncIterator](): AsyncIterableIterator<[Uint8Array, Addr]>;
   }
 
   export interface UnixListenOptions {
-    /** A Path to the Unix Socket. */
+    /** A Path to the Unix Socket. On Linux, a path starting with a null byte
+     * (`"\0"`) names a socket in the abstract namespace, which has no file
+     * system representation. Only supported for `"unixpacket"`. */
     path: string;
   }
 
+  export interface ListenOptions {
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Set `SO_REUSEPORT` on the socket, so several processes can listen on the
+     * same address, with the kernel distributing connections between them.
+     * Only supported on Unix. */
+    reusePort?: boolean;
+  }
+
+  export interface Conn {
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Enable or disable Nagle's algorithm (`TCP_NODELAY`). Only supported for
+     * TCP connections. */
+    setNoDelay(noDelay?: boolean): void;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Enable TCP keep-alive, sending probes after the connection has been idle
+     * for `interval` milliseconds, or disable it if `interval` is `null`. Only
+     * supported for TCP connections. */
+    setKeepAlive(interval: number | null): void;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Set the size of the send buffer of the socket (`SO_SNDBUF`) in bytes.
+     * Only supported for TCP connections. */
+    setSendBufferSize(size: number): void;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Set the size of the receive buffer of the socket (`SO_RCVBUF`) in bytes.
+     * Only supported for TCP connections. */
+    setRecvBufferSize(size: number): void;
+  }
+
   /** **UNSTABLE**: new API, yet to be vetted.
    *
    * Listen announces on the local transport address.
@@ -928,11 +1001,14 @@ declare namespace Deno {
    *
    * ```ts
    * const listener = Deno.listenDatagram({
-   *   address: "/foo/bar.sock",
+   *   path: "/foo/bar.sock",
    *   transport: "unixpacket"
    * });
    * ```
    *
+   * Messages received from sockets without a path name, such as unbound or
+   * abstract sockets, report a `null` path in the remote address.
+   *
    * Requires `allow-read` and `allow-write` permission. */
   export function listenDatagram(
     options: UnixListenOptions & { transport: "unixpacket" },
@@ -962,14 +1038,242 @@ declare namespace Deno {
     options: ConnectOptions | UnixConnectOptions,
   ): Promise<Conn>;
 
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * A connection secured by TLS. */
+  export interface TlsConn extends Conn {
+    /** The PEM encoded certificate chain presented by the peer, or `null` if
+     * the peer didn't present one. */
+    readonly peerCertificates: string[] | null;
+    /** The application protocol negotiated through ALPN, or `null` if none
+     * was negotiated. */
+    readonly alpnProtocol: string | null;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted. */
+  export interface TlsListener extends Listener {
+    /** Waits for and resolves to the next connection to the `Listener`. */
+    accept(): Promise<TlsConn>;
+  }
+
+  export interface ConnectTlsClientCertOptions {
+    /** PEM encoded certificate chain to present to the server. */
+    certChain: string;
+    /** PEM encoded private key of the first certificate of `certChain`. */
+    privateKey: string;
+  }
+
+  export interface ConnectTlsExtendedOptions {
+    /** PEM encoded CA certificates to trust in addition to Mozilla's root
+     * certificates. */
+    caCerts?: string[];
+    /** Application protocols to offer through ALPN, in order of preference. */
+    alpnProtocols?: string[];
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Establishes a secure connection over TLS, trusting the given PEM encoded
+   * CA certificates and offering the given application protocols through
+   * ALPN.
+   *
+   * ```ts
+   * const conn = await Deno.connectTls({
+   *   hostname: "example.com",
+   *   port: 443,
+   *   caCerts: [caCert],
+   *   alpnProtocols: ["h2", "http/1.1"],
+   * });
+   * console.log(conn.alpnProtocol);
+   * ```
+   *
+   * Requires `allow-net` permission.
+   */
+  export function connectTls(
+    options: ConnectTlsOptions & ConnectTlsExtendedOptions,
+  ): Promise<TlsConn>;
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Establishes a secure connection over TLS, presenting a client certificate
+   * to the server.
+   *
+   * ```ts
+   * const conn = await Deno.connectTls({
+   *   hostname: "mesh.internal",
+   *   port: 443,
+   *   certChain: Deno.readTextFileSync("./client.crt"),
+   *   privateKey: Deno.readTextFileSync("./client.key"),
+   * });
+   * ```
+   *
+   * Requires `allow-net` permission.
+   */
+  export function connectTls(
+    options:
+      & ConnectTlsOptions
+      & ConnectTlsClientCertOptions
+      & ConnectTlsExtendedOptions,
+  ): Promise<TlsConn>;
+
+  export interface ListenTlsClientAuthOptions {
+    /** PEM encoded CA certificates to verify client certificates against. */
+    clientCa: string;
+    /** Whether clients have to present a certificate. If `false`, clients
+     * without a certificate are accepted as well. Defaults to `true`. */
+    requireClientCert?: boolean;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Listen announces on the local transport address over TLS, verifying the
+   * certificates of clients. The certificate chain of a client is available
+   * as `peerCertificates` on the accepted connection.
+   *
+   * ```ts
+   * const listener = Deno.listenTls({
+   *   port: 443,
+   *   certFile: "./server.crt",
+   *   keyFile: "./server.key",
+   *   clientCa: Deno.readTextFileSync("./ca.crt"),
+   * });
+   * const conn = await listener.accept();
+   * console.log(conn.peerCertificates);
+   * ```
+   *
+   * Requires `allow-net` permission.
+   */
+  export function listenTls(
+    options:
+      & ListenTlsOptions
+      & ListenTlsClientAuthOptions
+      & Partial<ListenTlsExtendedOptions>,
+  ): TlsListener;
+
+  export interface ListenTlsExtendedOptions {
+    /** Application protocols to accept through ALPN, in order of
+     * preference. */
+    alpnProtocols: string[];
+    /** Certificates to serve per hostname requested through SNI. Clients
+     * which request another hostname, or none, are served the certificate in
+     * `certFile`. */
+    sniCertificates: Record<
+      string,
+      { certFile: string; keyFile: string } | { cert: string; key: string }
+    >;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Listen announces on the local transport address over TLS, negotiating
+   * application protocols through ALPN and serving a certificate per hostname
+   * requested through SNI.
+   *
+   * ```ts
+   * const listener = Deno.listenTls({
+   *   port: 443,
+   *   certFile: "./default.crt",
+   *   keyFile: "./default.key",
+   *   alpnProtocols: ["h2", "http/1.1"],
+   *   sniCertificates: {
+   *     "api.example.com": { certFile: "./api.crt", keyFile: "./api.key" },
+   *   },
+   * });
+   * ```
+   *
+   * Requires `allow-net` permission, and `allow-read` permission for all
+   * certificate and key files.
+   */
+  export function listenTls(
+    options: ListenTlsOptions & Partial<ListenTlsExtendedOptions>,
+  ): TlsListener;
+
+  export interface ListenTlsPemOptions extends ListenOptions {
+    /** PEM encoded server certificate chain. */
+    cert: string;
+    /** PEM encoded server private key. */
+    key: string;
+
+    transport?: "tcp";
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Listen announces on the local transport address over TLS, with the
+   * certificate chain and private key given as PEM encoded strings instead of
+   * files.
+   *
+   * ```ts
+   * const listener = Deno.listenTls({ port: 443, cert, key });
+   * ```
+   *
+   * Requires `allow-net` permission. `allow-read` permission is only required
+   * for files given in `sniCertificates`.
+   */
+  export function listenTls(
+    options:
+      & ListenTlsPemOptions
+      & Partial<ListenTlsClientAuthOptions>
+      & Partial<ListenTlsExtendedOptions>,
+  ): TlsListener;
+
   export interface StartTlsOptions {
     /** A literal IP address or host name that can be resolved to an IP address.
      * If not specified, defaults to `127.0.0.1`. */
     hostname?: string;
     /** Server certificate file. */
     certFile?: string;
+    /** PEM encoded CA certificates to trust in addition to Mozilla's root
+     * certificates. */
+    caCerts?: string[];
+    /** PEM encoded certificate chain to present to the server. */
+    certChain?: string;
+    /** PEM encoded private key of the first certificate of `certChain`. */
+    privateKey?: string;
+    /** Application protocols to offer through ALPN, in order of preference. */
+    alpnProtocols?: string[];
   }
 
+  export interface StartTlsServerOptions {
+    /** Server certificate file. Either `certFile` or `cert` is required. */
+    certFile?: string;
+    /** PEM encoded server certificate chain. */
+    cert?: string;
+    /** Server private key file. Either `keyFile` or `key` is required. */
+    keyFile?: string;
+    /** PEM encoded server private key. */
+    key?: string;
+    /** Application protocols to accept through ALPN, in order of
+     * preference. */
+    alpnProtocols?: string[];
+    /** Perform the server side of the handshake. */
+    server: true;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Start the server side of a TLS handshake on a connection accepted from a
+   * `Deno.Listener`, as in protocols such as SMTP and IMAP which upgrade a
+   * plain text connection with `STARTTLS`.
+   *
+   * ```ts
+   * const listener = Deno.listen({ port: 587 });
+   * const conn = await listener.accept();
+   * // ... negotiate STARTTLS
+   * const tlsConn = await Deno.startTls(conn, {
+   *   certFile: "./server.crt",
+   *   keyFile: "./server.key",
+   *   server: true,
+   * });
+   * ```
+   *
+   * Requires `allow-read` permission for `certFile` and `keyFile`.
+   */
+  export function startTls(
+    conn: Conn,
+    options: StartTlsServerOptions,
+  ): Promise<TlsConn>;
+
   /** **UNSTABLE**: new API, yet to be vetted.
    *
    * Start TLS handshake from an existing connection using
@@ -989,7 +1293,7 @@ declare namespace Deno {
   export function startTls(
     conn: Conn,
     options?: StartTlsOptions,
-  ): Promise<Conn>;
+  ): Promise<TlsConn>;
 
   /** **UNSTABLE**: The `signo` argument may change to require the Deno.Signal
    * enum.
@@ -1009,6 +1313,134 @@ declare namespace Deno {
    * Requires `allow-run` permission. */
   export function kill(pid: number, signo: number): void;
 
+  /** **UNSTABLE**: new API, yet to be vetted. */
+  export interface RunPtyOptions {
+    /** Arguments to pass. Note, the first element needs to be a path to the
+     * binary */
+    cmd: string[] | [URL, ...string[]];
+    cwd?: string;
+    env?: {
+      [key: string]: string;
+    };
+    /** The initial number of columns of the terminal. Defaults to 80. */
+    cols?: number;
+    /** The initial number of rows of the terminal. Defaults to 24. */
+    rows?: number;
+    /** Whether to put the terminal into raw mode, like `Deno.setRaw()`.
+     * Defaults to false. */
+    raw?: boolean;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * A process spawned by `Deno.runPty()`. */
+  export class PtyProcess extends Process {
+    /** The parent side of the pseudo-terminal, which is the stdin, stdout and
+     * stderr of the process. Reads resolve to `null` once the process and
+     * its descendants have exited. It can be passed to `Deno.isatty()`,
+     * `Deno.setRaw()` and `Deno.consoleSize()`. */
+    readonly pty: Reader & Writer & Closer & { readonly rid: number };
+    /** Change the size of the terminal, notifying the process with
+     * `SIGWINCH`. */
+    resize(cols: number, rows: number): void;
+    /** Close the pseudo-terminal and the process resource. */
+    close(): void;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Spawns a subprocess in a new session, with a pseudo-terminal as its
+   * controlling terminal and stdio. Only supported on Unix.
+   *
+   * ```ts
+   * const p = Deno.runPty({ cmd: ["vim", "notes.txt"], cols: 120, rows: 40 });
+   * await p.pty.write(new TextEncoder().encode(":q\r"));
+   * await p.status();
+   * p.close();
+   * ```
+   *
+   * Requires `allow-run` permission. */
+  export function runPty(options: RunPtyOptions): PtyProcess;
+
+  /** **UNSTABLE**: new API, yet to be vetted. */
+  export interface CommandOptions {
+    /** Arguments to pass to the command. */
+    args?: string[];
+    cwd?: string;
+    /** Don't inherit the environment variables of the current process, so the
+     * command only sees the variables given in `env`. Defaults to `false`. */
+    clearEnv?: boolean;
+    env?: {
+      [key: string]: string;
+    };
+    /** The user ID to run the command as. Only supported on Unix. */
+    uid?: number;
+    /** The group ID to run the command as. Only supported on Unix. */
+    gid?: number;
+    /** When the signal is aborted, the process is killed with `SIGKILL`. On
+     * Unix the process is started in its own process group, and the whole
+     * group is killed. Not supported by `spawnSync()`. */
+    signal?: AbortSignal;
+    /** Defaults to `"inherit"` for `spawn()` and to `"null"` otherwise. */
+    stdin?: "inherit" | "piped" | "null";
+    /** Defaults to `"inherit"` for `spawn()` and to `"piped"` otherwise. */
+    stdout?: "inherit" | "piped" | "null";
+    /** Defaults to `"inherit"` for `spawn()` and to `"piped"` otherwise. */
+    stderr?: "inherit" | "piped" | "null";
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted. */
+  export type CommandOutput = ProcessStatus & {
+    /** The collected stdout, empty if it wasn't piped. */
+    stdout: Uint8Array;
+    /** The collected stderr, empty if it wasn't piped. */
+    stderr: Uint8Array;
+  };
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * A process spawned by `Deno.Command.spawn()`. */
+  export class ChildProcess {
+    readonly pid: number;
+    readonly stdin: (Writer & Closer) | null;
+    readonly stdout: (Reader & Closer) | null;
+    readonly stderr: (Reader & Closer) | null;
+    /** Wait for the process to exit. Either this or `output()` can be called
+     * once. */
+    status(): Promise<ProcessStatus>;
+    /** Wait for the process to exit while collecting its piped stdout and
+     * stderr, which are closed afterwards. */
+    output(): Promise<CommandOutput>;
+    /** Send a signal to the process. Defaults to `SIGTERM`. */
+    kill(signo?: number): void;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * A command to run as a subprocess.
+   *
+   * ```ts
+   * const controller = new AbortController();
+   * setTimeout(() => controller.abort(), 5000);
+   * const command = new Deno.Command("git", {
+   *   args: ["status"],
+   *   signal: controller.signal,
+   * });
+   * const { success, stdout } = await command.output();
+   * ```
+   *
+   * Requires `allow-run` permission. */
+  export class Command {
+    constructor(command: string | URL, options?: CommandOptions);
+    /** Run the command to completion, collecting its stdout and stderr. */
+    output(): Promise<CommandOutput>;
+    /** Spawn the command, returning a handle to the running process. */
+    spawn(): ChildProcess;
+    /** Run the command to completion like `output()`, blocking until the
+     * process exits. */
+    spawnSync(): CommandOutput;
+  }
+
   /**  **UNSTABLE**: New API, yet to be vetted.  Additional consideration is still
    * necessary around the permissions required.
    *
@@ -1063,6 +1495,66 @@ declare namespace Deno {
    */
   export function ftruncate(rid: number, len?: number): Promise<void>;
 
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Synchronously acquire an advisory lock on the whole file, blocking until
+   * it's available. The lock is shared unless `exclusive` is `true`. It's
+   * released when the file is closed.
+   *
+   * ```ts
+   * const file = Deno.openSync("cache.json", { read: true, write: true });
+   * Deno.flockSync(file.rid, true);
+   * ```
+   *
+   * On Windows the lock is mandatory, so other processes can't read or write
+   * the locked file. */
+  export function flockSync(rid: number, exclusive?: boolean): void;
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Acquire an advisory lock on the whole file, resolving once it's
+   * available. The lock is shared unless `exclusive` is `true`. It's
+   * released when the file is closed.
+   *
+   * ```ts
+   * const file = await Deno.open("cache.json", { read: true, write: true });
+   * await Deno.flock(file.rid, true);
+   * ```
+   *
+   * On Windows the lock is mandatory, so other processes can't read or write
+   * the locked file. */
+  export function flock(rid: number, exclusive?: boolean): Promise<void>;
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Synchronously release a lock acquired with `Deno.flock()`. */
+  export function funlockSync(rid: number): void;
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Release a lock acquired with `Deno.flock()`. */
+  export function funlock(rid: number): Promise<void>;
+
+  export interface File {
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Acquire an advisory lock on the file. See `Deno.flock()`. */
+    lock(exclusive?: boolean): Promise<void>;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Synchronously acquire an advisory lock on the file. See
+     * `Deno.flockSync()`. */
+    lockSync(exclusive?: boolean): void;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Release the lock on the file. */
+    unlock(): Promise<void>;
+    /** **UNSTABLE**: new API, yet to be vetted.
+     *
+     * Synchronously release the lock on the file. */
+    unlockSync(): void;
+  }
+
   /** **UNSTABLE**: New API, yet to be vetted.
    * Synchronously returns a `Deno.FileInfo` for the given file stream.
    *
@@ -1110,6 +1602,37 @@ declare namespace Deno {
     /** A certificate authority to use when validating TLS certificates. Certificate data must be PEM encoded.
      */
     caData?: string;
+    /** Order dependent list of certificate stores to take the trusted root
+     * certificates from, `"mozilla"` (the default) and `"system"`. */
+    caStores?: string[];
+    /** A proxy server to route requests made with this client through. Hosts
+     * listed in the `NO_PROXY` environment variable bypass the proxy.
+     */
+    proxy?: Proxy;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   * The proxy server used by a [HttpClient].
+   *
+   * ```ts
+   * const client = Deno.createHttpClient({
+   *   proxy: {
+   *     url: "http://proxy.example.com:8080",
+   *     basicAuth: { username: "deno", password: "secret" },
+   *   },
+   * });
+   * ```
+   */
+  export interface Proxy {
+    /** The URL of the proxy server, using the `http` or `https` scheme. */
+    url: string;
+    /** Credentials sent to the proxy in the `Proxy-Authorization` header. */
+    basicAuth?: BasicAuth;
+  }
+
+  export interface BasicAuth {
+    username: string;
+    password: string;
   }
 
   /** **UNSTABLE**: New API, yet to be vetted.
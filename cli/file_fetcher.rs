@@ -2,6 +2,7 @@
 
 use crate::auth_tokens::AuthTokens;
 use crate::colors;
+use crate::http_cache;
 use crate::http_cache::HttpCache;
 use crate::http_util::create_http_client;
 use crate::http_util::fetch_once;
@@ -20,6 +21,7 @@ use deno_core::futures;
 use deno_core::futures::future::FutureExt;
 use deno_core::ModuleSpecifier;
 use deno_runtime::deno_fetch::reqwest;
+use deno_runtime::deno_fetch::Proxy;
 use std::collections::HashMap;
 use std::env;
 use std::fs;
@@ -85,13 +87,19 @@ pub enum CacheSetting {
   /// The cached source files should be used for local modules.  This is the
   /// default behavior of the CLI.
   Use,
+  /// Cached source files should only be used while they are fresh according
+  /// to their `Cache-Control`, `Expires` and `Last-Modified` headers.  Stale
+  /// files are revalidated with a conditional request, and are still used,
+  /// with a warning, when the server can't be reached.  This is the
+  /// equivalent of `--revalidate` in the CLI.
+  RespectHeaders,
 }
 
 impl CacheSetting {
   /// Returns if the cache should be used for a given specifier.
   pub fn should_use(&self, specifier: &ModuleSpecifier) -> bool {
     match self {
-      CacheSetting::ReloadAll => false,
+      CacheSetting::ReloadAll | CacheSetting::RespectHeaders => false,
       CacheSetting::Use | CacheSetting::Only => true,
       CacheSetting::ReloadSome(list) => {
         let mut url = specifier.clone();
@@ -115,6 +123,15 @@ impl CacheSetting {
   }
 }
 
+/// Returns if an error returned by `fetch_once` was caused by the remote server
+/// being unreachable.
+fn is_network_error(err: &AnyError) -> bool {
+  err
+    .downcast_ref::<reqwest::Error>()
+    .map(|err| err.is_connect() || err.is_timeout() || err.is_request())
+    .unwrap_or(false)
+}
+
 /// Fetch a source file from the local file system.
 fn fetch_local(specifier: &ModuleSpecifier) -> Result<File, AnyError> {
   let local = specifier.to_file_path().map_err(|_| {
@@ -323,7 +340,9 @@ impl FileFetcher {
     http_cache: HttpCache,
     cache_setting: CacheSetting,
     allow_remote: bool,
+    ca_stores: Option<Vec<String>>,
     ca_data: Option<Vec<u8>>,
+    proxy: Option<Proxy>,
   ) -> Result<Self, AnyError> {
     Ok(Self {
       auth_tokens: AuthTokens::new(env::var(DENO_AUTH_TOKENS).ok()),
@@ -331,7 +350,12 @@ impl FileFetcher {
       cache: Default::default(),
       cache_setting,
       http_cache,
-      http_client: create_http_client(get_user_agent(), ca_data)?,
+      http_client: create_http_client(
+        get_user_agent(),
+        ca_stores,
+        ca_data,
+        proxy,
+      )?,
     })
   }
 
@@ -489,12 +513,46 @@ impl FileFetcher {
       .boxed();
     }
 
-    info!("{} {}", colors::green("Download"), specifier);
-
-    let maybe_etag = match self.http_cache.get(specifier) {
-      Ok((_, headers)) => headers.get("etag").cloned(),
+    let maybe_cached_headers = match self.http_cache.get(specifier) {
+      Ok((_, headers)) => Some(headers),
       _ => None,
     };
+    let revalidate = self.cache_setting == CacheSetting::RespectHeaders
+      && maybe_cached_headers.is_some();
+
+    if revalidate {
+      let headers = maybe_cached_headers.as_ref().unwrap();
+      let is_fresh = self
+        .http_cache
+        .get_age(specifier)
+        .map(|age| http_cache::is_fresh(headers, age))
+        .unwrap_or(false);
+      if is_fresh {
+        match self.fetch_cached(specifier, redirect_limit) {
+          Ok(Some(file)) => {
+            return futures::future::ok(file).boxed();
+          }
+          Ok(None) => {}
+          Err(err) => {
+            return futures::future::err(err).boxed();
+          }
+        }
+      }
+      info!("{} {}", colors::green("Revalidate"), specifier);
+    } else {
+      info!("{} {}", colors::green("Download"), specifier);
+    }
+
+    let maybe_etag = maybe_cached_headers
+      .as_ref()
+      .and_then(|headers| headers.get("etag").cloned());
+    let maybe_last_modified = if revalidate {
+      maybe_cached_headers
+        .as_ref()
+        .and_then(|headers| headers.get("last-modified").cloned())
+    } else {
+      None
+    };
     let maybe_auth_token = self.auth_tokens.get(&specifier);
     let specifier = specifier.clone();
     let permissions = permissions.clone();
@@ -502,15 +560,34 @@ impl FileFetcher {
     let file_fetcher = self.clone();
     // A single pass of fetch either yields code or yields a redirect.
     async move {
-      match fetch_once(FetchOnceArgs {
+      let result = fetch_once(FetchOnceArgs {
         client,
         url: specifier.clone(),
         maybe_etag,
+        maybe_last_modified,
         maybe_auth_token,
       })
-      .await?
-      {
+      .await;
+      let result = match result {
+        Err(err) if revalidate && is_network_error(&err) => {
+          warn!(
+            "{} Unable to revalidate \"{}\", using the cached copy: {}",
+            colors::yellow("Warning"),
+            specifier,
+            err
+          );
+          if let Some(file) = file_fetcher.fetch_cached(&specifier, 10)? {
+            return Ok(file);
+          }
+          return Err(err);
+        }
+        result => result?,
+      };
+      match result {
         FetchOnceResult::NotModified => {
+          if revalidate {
+            file_fetcher.http_cache.touch(&specifier)?;
+          }
           let file = file_fetcher.fetch_cached(&specifier, 10)?.unwrap();
           Ok(file)
         }
@@ -614,9 +691,15 @@ mod tests {
       Rc::new(TempDir::new().expect("failed to create temp directory"))
     });
     let location = temp_dir.path().join("deps");
-    let file_fetcher =
-      FileFetcher::new(HttpCache::new(&location), cache_setting, true, None)
-        .expect("setup failed");
+    let file_fetcher = FileFetcher::new(
+      HttpCache::new(&location),
+      cache_setting,
+      true,
+      None,
+      None,
+      None,
+    )
+    .expect("setup failed");
     (file_fetcher, temp_dir)
   }
 
@@ -1060,6 +1143,8 @@ mod tests {
       CacheSetting::ReloadAll,
       true,
       None,
+      None,
+      None,
     )
     .expect("setup failed");
     let result = file_fetcher
@@ -1086,6 +1171,8 @@ mod tests {
       CacheSetting::Use,
       true,
       None,
+      None,
+      None,
     )
     .expect("could not create file fetcher");
     let specifier =
@@ -1113,6 +1200,8 @@ mod tests {
       CacheSetting::Use,
       true,
       None,
+      None,
+      None,
     )
     .expect("could not create file fetcher");
     let result = file_fetcher_02
@@ -1273,6 +1362,8 @@ mod tests {
       CacheSetting::Use,
       true,
       None,
+      None,
+      None,
     )
     .expect("could not create file fetcher");
     let specifier =
@@ -1303,6 +1394,8 @@ mod tests {
       CacheSetting::Use,
       true,
       None,
+      None,
+      None,
     )
     .expect("could not create file fetcher");
     let result = file_fetcher_02
@@ -1412,6 +1505,8 @@ mod tests {
       CacheSetting::Use,
       false,
       None,
+      None,
+      None,
     )
     .expect("could not create file fetcher");
     let specifier =
@@ -1438,6 +1533,8 @@ mod tests {
       CacheSetting::Only,
       true,
       None,
+      None,
+      None,
     )
     .expect("could not create file fetcher");
     let file_fetcher_02 = FileFetcher::new(
@@ -1445,6 +1542,8 @@ mod tests {
       CacheSetting::Use,
       true,
       None,
+      None,
+      None,
     )
     .expect("could not create file fetcher");
     let specifier =
@@ -1558,4 +1657,76 @@ mod tests {
                    \u{5E2}\u{5D5}\u{5DC}\u{5DD}\");\u{A}";
     test_fetch_remote_encoded("windows-1255", "windows-1255", expected).await;
   }
+
+  #[tokio::test]
+  async fn test_fetch_respect_headers_fresh() {
+    // Nothing listens on this port, so the module can only come from the
+    // cache.
+    let (file_fetcher, _) = setup(CacheSetting::RespectHeaders, None);
+    let specifier = resolve_url("http://localhost:4599/fresh.js").unwrap();
+    let mut headers = HashMap::new();
+    headers.insert("cache-control".to_string(), "max-age=3600".to_string());
+    file_fetcher
+      .http_cache
+      .set(&specifier, headers, b"console.log(\"fresh\");")
+      .unwrap();
+
+    let result = file_fetcher
+      .fetch_remote(&specifier, &Permissions::allow_all(), 1)
+      .await;
+    assert!(result.is_ok());
+    assert_eq!(result.unwrap().source, "console.log(\"fresh\");");
+  }
+
+  #[tokio::test]
+  async fn test_fetch_respect_headers_offline_fallback() {
+    let (file_fetcher, _) = setup(CacheSetting::RespectHeaders, None);
+    let specifier = resolve_url("http://localhost:4599/stale.js").unwrap();
+    let mut headers = HashMap::new();
+    headers.insert("cache-control".to_string(), "no-cache".to_string());
+    file_fetcher
+      .http_cache
+      .set(&specifier, headers, b"console.log(\"stale\");")
+      .unwrap();
+
+    let result = file_fetcher
+      .fetch_remote(&specifier, &Permissions::allow_all(), 1)
+      .await;
+    assert!(result.is_ok());
+    assert_eq!(result.unwrap().source, "console.log(\"stale\");");
+
+    // Without a cached copy the network error is surfaced.
+    let specifier = resolve_url("http://localhost:4599/missing.js").unwrap();
+    let result = file_fetcher
+      .fetch_remote(&specifier, &Permissions::allow_all(), 1)
+      .await;
+    assert!(result.is_err());
+  }
+
+  #[tokio::test]
+  async fn test_fetch_respect_headers_revalidates() {
+    let _http_server_guard = test_util::http_server();
+    let (file_fetcher, _) = setup(CacheSetting::RespectHeaders, None);
+    let specifier =
+      resolve_url("http://127.0.0.1:4545/etag_script.ts").unwrap();
+    let result = file_fetcher
+      .fetch_remote(&specifier, &Permissions::allow_all(), 1)
+      .await;
+    assert!(result.is_ok());
+    let cache_filename = file_fetcher
+      .http_cache
+      .get_cache_filename(&specifier)
+      .unwrap();
+    let old = filetime::FileTime::from_unix_time(0, 0);
+    filetime::set_file_mtime(&cache_filename, old).unwrap();
+
+    // The server answers the conditional request with a 304, which restarts
+    // the age of the cached copy.
+    let result = file_fetcher
+      .fetch_remote(&specifier, &Permissions::allow_all(), 1)
+      .await;
+    assert!(result.is_ok());
+    let age = file_fetcher.http_cache.get_age(&specifier).unwrap();
+    assert!(age < std::time::Duration::from_secs(3600));
+  }
 }
@@ -17,9 +17,83 @@ use std::fs::File;
 use std::io;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
 
 pub const CACHE_PERM: u32 = 0o644;
 
+/// Parse the directives of a `Cache-Control` header into lowercase
+/// `(name, value)` pairs.
+fn parse_cache_control(value: &str) -> Vec<(String, Option<String>)> {
+  value
+    .split(',')
+    .map(|directive| directive.trim())
+    .filter(|directive| !directive.is_empty())
+    .map(|directive| {
+      let mut parts = directive.splitn(2, '=');
+      let name = parts.next().unwrap().trim().to_lowercase();
+      let value = parts
+        .next()
+        .map(|value| value.trim().trim_matches('"').to_string());
+      (name, value)
+    })
+    .collect()
+}
+
+/// Returns the freshness lifetime of a response as described in
+/// [RFC 7234 section 4.2.1](https://tools.ietf.org/html/rfc7234#section-4.2.1),
+/// or `None` if the response must always be revalidated.
+pub fn freshness_lifetime(headers: &HeadersMap) -> Option<Duration> {
+  if let Some(cache_control) = headers.get("cache-control") {
+    let directives = parse_cache_control(cache_control);
+    if directives
+      .iter()
+      .any(|(name, _)| name == "no-cache" || name == "no-store")
+    {
+      return None;
+    }
+    if directives.iter().any(|(name, _)| name == "immutable") {
+      return Some(Duration::from_secs(u64::MAX));
+    }
+    if let Some(max_age) = directives
+      .iter()
+      .find(|(name, _)| name == "max-age")
+      .and_then(|(_, value)| value.as_ref())
+      .and_then(|value| value.parse::<u64>().ok())
+    {
+      return Some(Duration::from_secs(max_age));
+    }
+  }
+
+  let maybe_date = headers
+    .get("date")
+    .and_then(|date| httpdate::parse_http_date(date).ok());
+  if let Some(expires) = headers.get("expires") {
+    // An invalid date, such as "0", means the response is already expired.
+    let expires = httpdate::parse_http_date(expires).ok()?;
+    let date = maybe_date?;
+    return expires.duration_since(date).ok();
+  }
+
+  // Heuristic freshness: 10% of the time since the resource was last modified.
+  let last_modified = headers
+    .get("last-modified")
+    .and_then(|last_modified| httpdate::parse_http_date(last_modified).ok())?;
+  let date = maybe_date?;
+  date
+    .duration_since(last_modified)
+    .ok()
+    .map(|since_modified| since_modified / 10)
+}
+
+/// Returns if a response with the given headers, which was stored in the cache
+/// `age` ago, can be used without revalidating it with the server.
+pub fn is_fresh(headers: &HeadersMap, age: Duration) -> bool {
+  match freshness_lifetime(headers) {
+    Some(lifetime) => age < lifetime,
+    None => false,
+  }
+}
+
 /// Turn base of url (scheme, hostname, port) into a valid filename.
 /// This method replaces port part with a special string token (because
 /// ":" cannot be used in filename on some platforms).
@@ -91,7 +165,6 @@ impl Metadata {
     Ok(())
   }
 
-  #[cfg(test)]
   pub fn read(cache_filename: &Path) -> Result<Metadata, AnyError> {
     let metadata_filename = Metadata::filename(&cache_filename);
     let metadata = fs::read_to_string(metadata_filename)?;
@@ -136,6 +209,27 @@ impl HttpCache {
     Some(self.location.join(url_to_filename(url)?))
   }
 
+  /// Returns how long ago the cached copy of `url` was written, if it exists.
+  pub fn get_age(&self, url: &Url) -> Option<Duration> {
+    let cache_filename = self.get_cache_filename(url)?;
+    fs::metadata(cache_filename)
+      .and_then(|m| m.modified())
+      .ok()?
+      .elapsed()
+      .ok()
+  }
+
+  /// Mark the cached copy of `url` as revalidated, restarting its age.
+  pub fn touch(&self, url: &Url) -> Result<(), AnyError> {
+    let cache_filename = self.location.join(
+      url_to_filename(url)
+        .ok_or_else(|| generic_error("Can't convert url to filename."))?,
+    );
+    let now = filetime::FileTime::now();
+    filetime::set_file_mtime(cache_filename, now)?;
+    Ok(())
+  }
+
   // TODO(bartlomieju): this method should check headers file
   // and validate against ETAG/Last-modified-as headers.
   // ETAG check is currently done in `cli/file_fetcher.rs`.
@@ -275,4 +369,75 @@ mod tests {
       assert_eq!(p, PathBuf::from(expected));
     }
   }
+
+  fn headers(pairs: &[(&str, &str)]) -> HeadersMap {
+    pairs
+      .iter()
+      .map(|(k, v)| (k.to_string(), v.to_string()))
+      .collect()
+  }
+
+  #[test]
+  fn test_freshness_lifetime() {
+    let fixtures = vec![
+      (headers(&[]), None),
+      (
+        headers(&[("cache-control", "public, max-age=3600")]),
+        Some(Duration::from_secs(3600)),
+      ),
+      (
+        headers(&[("cache-control", "max-age=3600, no-cache")]),
+        None,
+      ),
+      (headers(&[("cache-control", "no-store")]), None),
+      (
+        headers(&[("cache-control", "max-age=31536000, immutable")]),
+        Some(Duration::from_secs(u64::MAX)),
+      ),
+      (
+        headers(&[
+          ("date", "Wed, 21 Oct 2015 07:28:00 GMT"),
+          ("expires", "Wed, 21 Oct 2015 08:28:00 GMT"),
+        ]),
+        Some(Duration::from_secs(3600)),
+      ),
+      (
+        headers(&[("date", "Wed, 21 Oct 2015 07:28:00 GMT"), ("expires", "0")]),
+        None,
+      ),
+      (
+        headers(&[
+          ("date", "Wed, 21 Oct 2015 07:28:00 GMT"),
+          ("last-modified", "Wed, 21 Oct 2015 07:11:20 GMT"),
+        ]),
+        Some(Duration::from_secs(100)),
+      ),
+    ];
+    for (headers, expected) in fixtures {
+      assert_eq!(freshness_lifetime(&headers), expected, "{:?}", headers);
+    }
+  }
+
+  #[test]
+  fn test_is_fresh() {
+    let headers = headers(&[("cache-control", "max-age=60")]);
+    assert!(is_fresh(&headers, Duration::from_secs(10)));
+    assert!(!is_fresh(&headers, Duration::from_secs(60)));
+    assert!(!is_fresh(&HashMap::new(), Duration::from_secs(0)));
+  }
+
+  #[test]
+  fn test_get_age_and_touch() {
+    let dir = TempDir::new().unwrap();
+    let cache = HttpCache::new(dir.path());
+    let url = Url::parse("https://deno.land/x/welcome.ts").unwrap();
+    assert!(cache.get_age(&url).is_none());
+    cache.set(&url, HashMap::new(), b"hello").unwrap();
+    let cache_filename = cache.get_cache_filename(&url).unwrap();
+    let old = filetime::FileTime::from_unix_time(0, 0);
+    filetime::set_file_mtime(&cache_filename, old).unwrap();
+    assert!(cache.get_age(&url).unwrap() > Duration::from_secs(3600));
+    cache.touch(&url).unwrap();
+    assert!(cache.get_age(&url).unwrap() < Duration::from_secs(3600));
+  }
 }
@@ -5,14 +5,12 @@ use crate::auth_tokens::AuthToken;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::url::Url;
-use deno_runtime::deno_fetch::reqwest;
-use deno_runtime::deno_fetch::reqwest::header::HeaderMap;
+use deno_runtime::deno_fetch;
 use deno_runtime::deno_fetch::reqwest::header::HeaderValue;
 use deno_runtime::deno_fetch::reqwest::header::AUTHORIZATION;
+use deno_runtime::deno_fetch::reqwest::header::IF_MODIFIED_SINCE;
 use deno_runtime::deno_fetch::reqwest::header::IF_NONE_MATCH;
 use deno_runtime::deno_fetch::reqwest::header::LOCATION;
-use deno_runtime::deno_fetch::reqwest::header::USER_AGENT;
-use deno_runtime::deno_fetch::reqwest::redirect::Policy;
 use deno_runtime::deno_fetch::reqwest::Client;
 use deno_runtime::deno_fetch::reqwest::StatusCode;
 use std::collections::HashMap;
@@ -21,23 +19,11 @@ use std::collections::HashMap;
 /// proxies and doesn't follow redirects.
 pub fn create_http_client(
   user_agent: String,
+  ca_stores: Option<Vec<String>>,
   ca_data: Option<Vec<u8>>,
+  proxy: Option<deno_fetch::Proxy>,
 ) -> Result<Client, AnyError> {
-  let mut headers = HeaderMap::new();
-  headers.insert(USER_AGENT, user_agent.parse().unwrap());
-  let mut builder = Client::builder()
-    .redirect(Policy::none())
-    .default_headers(headers)
-    .use_rustls_tls();
-
-  if let Some(ca_data) = ca_data {
-    let cert = reqwest::Certificate::from_pem(&ca_data)?;
-    builder = builder.add_root_certificate(cert);
-  }
-
-  builder
-    .build()
-    .map_err(|e| generic_error(format!("Unable to build http client: {}", e)))
+  deno_fetch::create_http_client(Some(user_agent), ca_stores, ca_data, proxy)
 }
 
 /// Construct the next uri based on base uri and location header fragment
@@ -84,6 +70,7 @@ pub struct FetchOnceArgs {
   pub client: Client,
   pub url: Url,
   pub maybe_etag: Option<String>,
+  pub maybe_last_modified: Option<String>,
   pub maybe_auth_token: Option<AuthToken>,
 }
 
@@ -101,6 +88,10 @@ pub async fn fetch_once(
     let if_none_match_val = HeaderValue::from_str(&etag).unwrap();
     request = request.header(IF_NONE_MATCH, if_none_match_val);
   }
+  if let Some(last_modified) = args.maybe_last_modified {
+    let if_modified_since_val = HeaderValue::from_str(&last_modified).unwrap();
+    request = request.header(IF_MODIFIED_SINCE, if_modified_since_val);
+  }
   if let Some(auth_token) = args.maybe_auth_token {
     let authorization_val =
       HeaderValue::from_str(&auth_token.to_string()).unwrap();
@@ -170,7 +161,7 @@ mod tests {
   use std::fs::read;
 
   fn create_test_client(ca_data: Option<Vec<u8>>) -> Client {
-    create_http_client("test_client".to_string(), ca_data).unwrap()
+    create_http_client("test_client".to_string(), None, ca_data, None).unwrap()
   }
 
   #[tokio::test]
@@ -184,6 +175,7 @@ mod tests {
       client,
       url,
       maybe_etag: None,
+      maybe_last_modified: None,
       maybe_auth_token: None,
     })
     .await;
@@ -210,6 +202,7 @@ mod tests {
       client,
       url,
       maybe_etag: None,
+      maybe_last_modified: None,
       maybe_auth_token: None,
     })
     .await;
@@ -235,6 +228,7 @@ mod tests {
       client: client.clone(),
       url: url.clone(),
       maybe_etag: None,
+      maybe_last_modified: None,
       maybe_auth_token: None,
     })
     .await;
@@ -254,6 +248,7 @@ mod tests {
       client,
       url,
       maybe_etag: Some("33a64df551425fcc55e".to_string()),
+      maybe_last_modified: None,
       maybe_auth_token: None,
     })
     .await;
@@ -273,6 +268,7 @@ mod tests {
       client,
       url,
       maybe_etag: None,
+      maybe_last_modified: None,
       maybe_auth_token: None,
     })
     .await;
@@ -304,6 +300,7 @@ mod tests {
       client,
       url,
       maybe_etag: None,
+      maybe_last_modified: None,
       maybe_auth_token: None,
     })
     .await;
@@ -361,6 +358,7 @@ mod tests {
 
     let client = create_http_client(
       version::get_user_agent(),
+      None,
       Some(
         read(
           test_util::root_path()
@@ -370,12 +368,14 @@ mod tests {
         )
         .unwrap(),
       ),
+      None,
     )
     .unwrap();
     let result = fetch_once(FetchOnceArgs {
       client,
       url,
       maybe_etag: None,
+      maybe_last_modified: None,
       maybe_auth_token: None,
     })
     .await;
@@ -399,6 +399,7 @@ mod tests {
     .unwrap();
     let client = create_http_client(
       version::get_user_agent(),
+      None,
       Some(
         read(
           test_util::root_path()
@@ -408,12 +409,14 @@ mod tests {
         )
         .unwrap(),
       ),
+      None,
     )
     .unwrap();
     let result = fetch_once(FetchOnceArgs {
       client,
       url,
       maybe_etag: None,
+      maybe_last_modified: None,
       maybe_auth_token: None,
     })
     .await;
@@ -436,6 +439,7 @@ mod tests {
     let url = Url::parse("https://localhost:5545/etag_script.ts").unwrap();
     let client = create_http_client(
       version::get_user_agent(),
+      None,
       Some(
         read(
           test_util::root_path()
@@ -445,12 +449,14 @@ mod tests {
         )
         .unwrap(),
       ),
+      None,
     )
     .unwrap();
     let result = fetch_once(FetchOnceArgs {
       client: client.clone(),
       url: url.clone(),
       maybe_etag: None,
+      maybe_last_modified: None,
       maybe_auth_token: None,
     })
     .await;
@@ -471,6 +477,7 @@ mod tests {
       client,
       url,
       maybe_etag: Some("33a64df551425fcc55e".to_string()),
+      maybe_last_modified: None,
       maybe_auth_token: None,
     })
     .await;
@@ -487,6 +494,7 @@ mod tests {
     .unwrap();
     let client = create_http_client(
       version::get_user_agent(),
+      None,
       Some(
         read(
           test_util::root_path()
@@ -496,12 +504,14 @@ mod tests {
         )
         .unwrap(),
       ),
+      None,
     )
     .unwrap();
     let result = fetch_once(FetchOnceArgs {
       client,
       url,
       maybe_etag: None,
+      maybe_last_modified: None,
       maybe_auth_token: None,
     })
     .await;
@@ -529,6 +539,7 @@ mod tests {
       client,
       url,
       maybe_etag: None,
+      maybe_last_modified: None,
       maybe_auth_token: None,
     })
     .await;
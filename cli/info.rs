@@ -6,9 +6,14 @@ use crate::media_type::MediaType;
 
 use deno_core::resolve_url;
 use deno_core::serde::Serialize;
+use deno_core::url::Position;
 use deno_core::ModuleSpecifier;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt;
+use std::fmt::Write;
 use std::iter::Iterator;
 use std::path::PathBuf;
 
@@ -19,6 +24,21 @@ const CHILD_NO_DEPS_CONNECTOR: char = '─';
 const VERTICAL_CONNECTOR: char = '│';
 const EMPTY_CONNECTOR: char = ' ';
 
+/// The number of import chains after which `ModuleGraphInfo::why` stops
+/// searching, as the number of chains can grow exponentially with the size of
+/// the graph.
+const MAX_IMPORT_CHAINS: usize = 100;
+
+lazy_static! {
+  /// A path segment which carries the version of a library, such as
+  /// `std@0.88.0` or `oak@v6.5.0`.
+  static ref VERSIONED_SEGMENT_RE: Regex =
+    Regex::new(r"^(?P<name>[^@]+)@(?P<version>v?\d[^/]*)$").unwrap();
+  /// A path segment which only consists of a version, such as `v1.2.3`.
+  static ref VERSION_SEGMENT_RE: Regex =
+    Regex::new(r"^v?\d+\.\d+(\.\d+)?([-+][0-9A-Za-z.-]+)?$").unwrap();
+}
+
 #[derive(Debug, Serialize, Ord, PartialOrd, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ModuleGraphInfoDep {
@@ -240,6 +260,315 @@ impl fmt::Display for ModuleGraphInfo {
   }
 }
 
+impl ModuleGraphInfo {
+  fn get_module(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Option<&ModuleGraphInfoMod> {
+    self.modules.iter().find(|m| &m.specifier == specifier)
+  }
+
+  /// Find the libraries which are imported at more than one version, where
+  /// the specifiers of a library only differ by a version segment.
+  pub fn dupes(&self) -> Vec<DuplicateLibrary> {
+    let mut libraries: BTreeMap<String, HashMap<String, Vec<ModuleSpecifier>>> =
+      BTreeMap::new();
+    for module in self.modules.iter() {
+      if let Some((name, version)) = split_version(&module.specifier) {
+        libraries
+          .entry(name)
+          .or_default()
+          .entry(version)
+          .or_default()
+          .push(module.specifier.clone());
+      }
+    }
+    libraries
+      .into_iter()
+      .filter(|(_, versions)| versions.len() > 1)
+      .map(|(name, versions)| {
+        let mut versions: Vec<DuplicateVersion> = versions
+          .into_iter()
+          .map(|(version, modules)| DuplicateVersion { version, modules })
+          .collect();
+        versions.sort_by(|a, b| {
+          version_key(&a.version)
+            .cmp(&version_key(&b.version))
+            .then_with(|| a.version.cmp(&b.version))
+        });
+        DuplicateLibrary { name, versions }
+      })
+      .collect()
+  }
+
+  /// Find the chains of imports which lead from the root module to `target`,
+  /// up to `MAX_IMPORT_CHAINS` of them.
+  pub fn why(&self, target: &ModuleSpecifier) -> ImportChains {
+    // Only descend into modules from which the target can be reached, so
+    // unrelated parts of the graph are not walked for every chain.
+    let mut importers: HashMap<&ModuleSpecifier, Vec<&ModuleSpecifier>> =
+      HashMap::new();
+    for module in self.modules.iter() {
+      for dep in module.dependencies.iter() {
+        for specifier in dep.maybe_code.iter().chain(dep.maybe_type.iter()) {
+          importers
+            .entry(specifier)
+            .or_default()
+            .push(&module.specifier);
+        }
+      }
+    }
+    let mut reaches_target = HashSet::new();
+    let mut pending = vec![target];
+    while let Some(specifier) = pending.pop() {
+      if reaches_target.insert(specifier.clone()) {
+        if let Some(importers) = importers.get(specifier) {
+          pending.extend(importers.iter().copied());
+        }
+      }
+    }
+
+    let mut chains = Vec::new();
+    if reaches_target.contains(&self.root) {
+      let mut chain = vec![self.root.clone()];
+      self.collect_chains(target, &reaches_target, &mut chain, &mut chains);
+    }
+    let truncated = chains.len() > MAX_IMPORT_CHAINS;
+    chains.truncate(MAX_IMPORT_CHAINS);
+    ImportChains {
+      target: target.clone(),
+      chains,
+      truncated,
+    }
+  }
+
+  fn collect_chains(
+    &self,
+    target: &ModuleSpecifier,
+    reaches_target: &HashSet<ModuleSpecifier>,
+    chain: &mut Vec<ModuleSpecifier>,
+    chains: &mut Vec<Vec<ModuleSpecifier>>,
+  ) {
+    // One chain past the limit is collected so the caller can tell the result
+    // was truncated.
+    if chains.len() > MAX_IMPORT_CHAINS {
+      return;
+    }
+    let current = chain.last().unwrap().clone();
+    if &current == target {
+      chains.push(chain.clone());
+      return;
+    }
+    let module = match self.get_module(&current) {
+      Some(module) => module,
+      None => return,
+    };
+    let mut next: Vec<&ModuleSpecifier> = Vec::new();
+    for specifier in module
+      .dependencies
+      .iter()
+      .flat_map(|dep| dep.maybe_code.iter().chain(dep.maybe_type.iter()))
+    {
+      if reaches_target.contains(specifier)
+        && !chain.contains(specifier)
+        && !next.contains(&specifier)
+      {
+        next.push(specifier);
+      }
+    }
+    for specifier in next {
+      chain.push(specifier.clone());
+      self.collect_chains(target, reaches_target, chain, chains);
+      chain.pop();
+    }
+  }
+
+  /// Render the module graph in the Graphviz DOT format. Type dependencies are
+  /// drawn dashed and dynamic imports dotted.
+  pub fn to_dot(&self) -> String {
+    let mut out = String::new();
+    let root = escape_dot(self.root.as_str());
+    writeln!(out, "digraph \"{}\" {{", root).unwrap();
+    writeln!(out, "  node [shape=box];").unwrap();
+    for module in self.modules.iter() {
+      let specifier = escape_dot(module.specifier.as_str());
+      let mut attrs = if let Some(size) = module.size {
+        format!("label=\"{}\\n({})\"", specifier, human_size(size as f64))
+      } else {
+        format!("label=\"{}\"", specifier)
+      };
+      if module.error.is_some() {
+        attrs.push_str(", color=red");
+      }
+      if module.specifier == self.root {
+        attrs.push_str(", style=bold");
+      }
+      writeln!(out, "  \"{}\" [{}];", specifier, attrs).unwrap();
+    }
+    for module in self.modules.iter() {
+      let from = escape_dot(module.specifier.as_str());
+      for dep in module.dependencies.iter() {
+        let style = if dep.is_dynamic {
+          " [style=dotted]"
+        } else {
+          ""
+        };
+        if let Some(code) = &dep.maybe_code {
+          let to = escape_dot(code.as_str());
+          writeln!(out, "  \"{}\" -> \"{}\"{};", from, to, style).unwrap();
+        }
+        if let Some(types) = &dep.maybe_type {
+          let to = escape_dot(types.as_str());
+          writeln!(out, "  \"{}\" -> \"{}\" [style=dashed];", from, to)
+            .unwrap();
+        }
+      }
+    }
+    writeln!(out, "}}").unwrap();
+    out
+  }
+}
+
+fn escape_dot(s: &str) -> String {
+  s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Split a specifier into the name of the library it belongs to, with the
+/// version replaced by `*`, and the version of the library. Returns `None` if
+/// the specifier does not contain a version segment.
+fn split_version(specifier: &ModuleSpecifier) -> Option<(String, String)> {
+  let mut name = specifier[..Position::BeforePath].to_string();
+  for (i, segment) in specifier.path_segments()?.enumerate() {
+    name.push('/');
+    if let Some(caps) = VERSIONED_SEGMENT_RE.captures(segment) {
+      name.push_str(&caps["name"]);
+      name.push_str("@*");
+      return Some((name, caps["version"].to_string()));
+    } else if i > 0 && VERSION_SEGMENT_RE.is_match(segment) {
+      name.push('*');
+      return Some((name, segment.to_string()));
+    }
+    name.push_str(segment);
+  }
+  None
+}
+
+/// A key which orders versions by their numeric components, so that `0.9.0`
+/// sorts before `0.10.0`.
+fn version_key(version: &str) -> Vec<u64> {
+  version
+    .split(|c: char| !c.is_ascii_digit())
+    .filter(|part| !part.is_empty())
+    .map(|part| part.parse::<u64>().unwrap_or(u64::MAX))
+    .collect()
+}
+
+/// A library which is imported at more than one version.
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateLibrary {
+  /// The specifier of the library with its version replaced by `*`, for
+  /// example `https://deno.land/std@*`.
+  pub name: String,
+  pub versions: Vec<DuplicateVersion>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateVersion {
+  pub version: String,
+  pub modules: Vec<ModuleSpecifier>,
+}
+
+impl fmt::Display for DuplicateLibrary {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(
+      f,
+      "{} {}",
+      colors::bold(&self.name),
+      colors::gray(format!("({} versions)", self.versions.len()))
+    )?;
+    let count = self.versions.len();
+    for (idx, version) in self.versions.iter().enumerate() {
+      let connector = if idx == count - 1 {
+        LAST_SIBLING_CONNECTOR
+      } else {
+        SIBLING_CONNECTOR
+      };
+      writeln!(
+        f,
+        "{} {} {}",
+        colors::gray(format!("{}──", connector)),
+        version.version,
+        colors::gray(format!(
+          "({} module{})",
+          version.modules.len(),
+          if version.modules.len() == 1 { "" } else { "s" }
+        ))
+      )?;
+    }
+    Ok(())
+  }
+}
+
+/// The import chains which lead from the root module to `target`.
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportChains {
+  pub target: ModuleSpecifier,
+  pub chains: Vec<Vec<ModuleSpecifier>>,
+  /// Set when there were more than `MAX_IMPORT_CHAINS` chains and only the
+  /// first ones were kept.
+  pub truncated: bool,
+}
+
+impl fmt::Display for ImportChains {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if self.chains.is_empty() {
+      return writeln!(
+        f,
+        "{} is not imported by the module graph",
+        colors::bold(&self.target)
+      );
+    }
+    writeln!(
+      f,
+      "{} is reached by {}{} import chain{}",
+      colors::bold(&self.target),
+      if self.truncated { "more than " } else { "" },
+      self.chains.len(),
+      if self.chains.len() == 1 { "" } else { "s" }
+    )?;
+    if self.truncated {
+      writeln!(
+        f,
+        "{}",
+        colors::gray(format!(
+          "(only the first {} are shown)",
+          self.chains.len()
+        ))
+      )?;
+    }
+    for chain in self.chains.iter() {
+      writeln!(f)?;
+      for (depth, specifier) in chain.iter().enumerate() {
+        if depth == 0 {
+          writeln!(f, "{}", specifier)?;
+        } else {
+          writeln!(
+            f,
+            "{}{} {}",
+            " ".repeat((depth - 1) * 2),
+            colors::gray(format!("{}─", LAST_SIBLING_CONNECTOR)),
+            specifier
+          )?;
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
 /// An entry in the `ModuleInfoMap` the provides the size of the module and
 /// a vector of its dependencies, which should also be available as entries
 /// in the map.
@@ -430,4 +759,150 @@ https://deno.land/x/a.ts (123B)
       })
     );
   }
+
+  #[test]
+  fn test_module_graph_info_dupes() {
+    let mut fixture = get_fixture();
+    assert!(fixture.dupes().is_empty());
+    for specifier in &[
+      "https://deno.land/std@0.88.0/fmt/colors.ts",
+      "https://deno.land/std@0.88.0/path/mod.ts",
+      "https://deno.land/std@0.100.0/fmt/colors.ts",
+      "https://deno.land/std/fmt/colors.ts",
+      "https://esm.sh/v1.2.3/lodash.js",
+      "https://cdn.example.com/x/oak/v6.5.0/mod.ts",
+    ] {
+      fixture.modules.push(ModuleGraphInfoMod {
+        specifier: resolve_url(specifier).unwrap(),
+        ..Default::default()
+      });
+    }
+    let dupes = fixture.dupes();
+    assert_eq!(dupes.len(), 1);
+    assert_eq!(dupes[0].name, "https://deno.land/std@*");
+    assert_eq!(dupes[0].versions.len(), 2);
+    assert_eq!(dupes[0].versions[0].version, "0.88.0");
+    assert_eq!(dupes[0].versions[0].modules.len(), 2);
+    assert_eq!(dupes[0].versions[1].version, "0.100.0");
+    let actual = colors::strip_ansi_codes(&dupes[0].to_string()).to_string();
+    assert_eq!(
+      actual,
+      r#"https://deno.land/std@* (2 versions)
+├── 0.88.0 (2 modules)
+└── 0.100.0 (1 module)
+"#
+    );
+  }
+
+  #[test]
+  fn test_split_version() {
+    let split = |s: &str| split_version(&resolve_url(s).unwrap());
+    assert_eq!(
+      split("https://deno.land/x/oak@v6.5.0/mod.ts"),
+      Some((
+        "https://deno.land/x/oak@*".to_string(),
+        "v6.5.0".to_string()
+      ))
+    );
+    assert_eq!(
+      split("https://cdn.example.com/oak/v6.5.0/mod.ts"),
+      Some((
+        "https://cdn.example.com/oak/*".to_string(),
+        "v6.5.0".to_string()
+      ))
+    );
+    assert_eq!(split("https://deno.land/x/oak/mod.ts"), None);
+    assert_eq!(split("https://example.com/1.2.3/mod.ts"), None);
+  }
+
+  #[test]
+  fn test_module_graph_info_why() {
+    let fixture = get_fixture();
+    let c_dts = resolve_url("https://deno.land/x/c.d.ts").unwrap();
+    let why = fixture.why(&c_dts);
+    assert_eq!(why.chains.len(), 1);
+    assert_eq!(
+      why.chains[0],
+      vec![
+        resolve_url("https://deno.land/x/a.ts").unwrap(),
+        resolve_url("https://deno.land/x/b.ts").unwrap(),
+        c_dts.clone(),
+      ]
+    );
+    let actual = colors::strip_ansi_codes(&why.to_string()).to_string();
+    assert_eq!(
+      actual,
+      r#"https://deno.land/x/c.d.ts is reached by 1 import chain
+
+https://deno.land/x/a.ts
+└─ https://deno.land/x/b.ts
+  └─ https://deno.land/x/c.d.ts
+"#
+    );
+
+    let other = resolve_url("https://deno.land/x/other.ts").unwrap();
+    assert!(fixture.why(&other).chains.is_empty());
+  }
+
+  #[test]
+  fn test_module_graph_info_why_truncated() {
+    // Every module of a layer imports both modules of the next layer, so
+    // there are 2^32 chains from the root to the last module.
+    let specifier = |layer: usize, idx: usize| {
+      resolve_url(&format!("https://deno.land/x/{}_{}.ts", layer, idx)).unwrap()
+    };
+    let layers = 32;
+    let mut modules = Vec::new();
+    for layer in 0..layers {
+      for idx in 0..2 {
+        let dependencies = (0..2)
+          .map(|next| ModuleGraphInfoDep {
+            specifier: format!("./{}_{}.ts", layer + 1, next),
+            is_dynamic: false,
+            maybe_code: Some(specifier(layer + 1, next)),
+            maybe_type: None,
+          })
+          .collect();
+        modules.push(ModuleGraphInfoMod {
+          specifier: specifier(layer, idx),
+          dependencies,
+          ..Default::default()
+        });
+      }
+    }
+    let target = specifier(layers, 0);
+    modules.push(ModuleGraphInfoMod {
+      specifier: target.clone(),
+      ..Default::default()
+    });
+    let info = ModuleGraphInfo {
+      root: specifier(0, 0),
+      modules,
+      size: 0,
+    };
+    let why = info.why(&target);
+    assert!(why.truncated);
+    assert_eq!(why.chains.len(), MAX_IMPORT_CHAINS);
+    assert!(why.chains.iter().all(|c| c.len() == layers + 1));
+  }
+
+  #[test]
+  fn test_module_graph_info_dot() {
+    let fixture = get_fixture();
+    let actual = fixture.to_dot();
+    assert_eq!(
+      actual,
+      r#"digraph "https://deno.land/x/a.ts" {
+  node [shape=box];
+  "https://deno.land/x/a.ts" [label="https://deno.land/x/a.ts\n(123B)", style=bold];
+  "https://deno.land/x/b.ts" [label="https://deno.land/x/b.ts\n(456B)"];
+  "https://deno.land/x/c.js" [label="https://deno.land/x/c.js\n(789B)"];
+  "https://deno.land/x/c.d.ts" [label="https://deno.land/x/c.d.ts\n(999B)"];
+  "https://deno.land/x/a.ts" -> "https://deno.land/x/b.ts";
+  "https://deno.land/x/b.ts" -> "https://deno.land/x/c.js";
+  "https://deno.land/x/b.ts" -> "https://deno.land/x/c.d.ts" [style=dashed];
+}
+"#
+    );
+  }
 }
@@ -113,6 +113,8 @@ fn create_web_worker_callback(
         .map_or(false, |l| l == log::Level::Debug),
       unstable: program_state.flags.unstable,
       ca_data: program_state.ca_data.clone(),
+      ca_stores: program_state.ca_stores.clone(),
+      proxy: program_state.proxy.clone(),
       user_agent: version::get_user_agent(),
       seed: program_state.flags.seed,
       module_loader,
@@ -189,6 +191,8 @@ pub fn create_main_worker(
       .map_or(false, |l| l == log::Level::Debug),
     unstable: program_state.flags.unstable,
     ca_data: program_state.ca_data.clone(),
+    ca_stores: program_state.ca_stores.clone(),
+    proxy: program_state.proxy.clone(),
     user_agent: version::get_user_agent(),
     seed: program_state.flags.seed,
     js_error_create_fn: Some(js_error_create_fn),
@@ -295,6 +299,7 @@ pub fn get_types(unstable: bool) -> String {
   types
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn compile_command(
   flags: Flags,
   source_file: String,
@@ -302,6 +307,9 @@ async fn compile_command(
   args: Vec<String>,
   target: Option<String>,
   lite: bool,
+  entrypoints: Vec<String>,
+  include: Vec<String>,
+  snapshot: bool,
 ) -> Result<(), AnyError> {
   if !flags.unstable {
     exit_unstable("compile");
@@ -322,8 +330,12 @@ async fn compile_command(
     "An executable name was not provided. One could not be inferred from the URL. Aborting.",
   ))?;
 
+  let mut module_specifiers = vec![module_specifier.clone()];
+  for entrypoint in entrypoints.iter() {
+    module_specifiers.push(resolve_url_or_path(entrypoint)?);
+  }
   let module_graph = create_module_graph_and_maybe_check(
-    module_specifier.clone(),
+    module_specifiers,
     program_state.clone(),
     debug,
   )
@@ -331,25 +343,57 @@ async fn compile_command(
 
   info!(
     "{} {}",
-    colors::green("Bundle"),
+    colors::green("Compile"),
     module_specifier.to_string()
   );
-  let bundle_str = bundle_module_graph(module_graph, flags, debug)?;
 
-  info!(
-    "{} {}",
-    colors::green("Compile"),
-    module_specifier.to_string()
+  let (runtime_modules, maybe_ignored_options) =
+    module_graph.get_runtime_modules(flags.config_path.clone())?;
+  match maybe_ignored_options {
+    Some(ignored_options) if flags.no_check => {
+      eprintln!("{}", ignored_options);
+    }
+    _ => {}
+  }
+  let included_files = tools::standalone::collect_included_files(&include)?;
+  // Local modules and included files are embedded relative to the compile
+  // root, so the binary doesn't contain paths of this machine.
+  let maybe_root =
+    tools::standalone::compile_root(&runtime_modules, &included_files);
+  let main_module = tools::standalone::embedded_specifier(
+    &module_specifier,
+    maybe_root.as_deref(),
+  );
+  let modules = tools::standalone::embed_modules(
+    runtime_modules,
+    module_graph.get_redirects(),
+    maybe_root.as_deref(),
   );
 
   // Select base binary based on `target` and `lite` arguments
   let original_binary =
     tools::standalone::get_base_binary(deno_dir, target, lite).await?;
 
+  let mut metadata =
+    tools::standalone::create_metadata(main_module, run_flags)?;
+  let maybe_snapshot = if snapshot {
+    info!(
+      "{} {}",
+      colors::green("Snapshot"),
+      module_specifier.to_string()
+    );
+    Some(standalone::create_snapshot(modules.clone(), &mut metadata).await?)
+  } else {
+    None
+  };
+
   let final_bin = tools::standalone::create_standalone_binary(
     original_binary,
-    bundle_str,
-    run_flags,
+    metadata,
+    modules,
+    included_files,
+    maybe_root.as_deref(),
+    maybe_snapshot,
   )?;
 
   info!("{} {}", colors::green("Emit"), output.display());
@@ -363,6 +407,9 @@ async fn info_command(
   flags: Flags,
   maybe_specifier: Option<String>,
   json: bool,
+  dupes: bool,
+  maybe_why: Option<String>,
+  dot: bool,
 ) -> Result<(), AnyError> {
   if json && !flags.unstable {
     exit_unstable("--json");
@@ -385,7 +432,47 @@ async fn info_command(
     let graph = builder.get_graph();
     let info = graph.info()?;
 
-    if json {
+    if dupes {
+      if dot {
+        return Err(generic_error(
+          "--dupes does not support the \"dot\" format.",
+        ));
+      }
+      let dupes = info.dupes();
+      if json {
+        write_json_to_stdout(&json!(dupes))
+      } else if dupes.is_empty() {
+        println!("No library is imported at more than one version.");
+        Ok(())
+      } else {
+        let output: Vec<String> = dupes.iter().map(|d| d.to_string()).collect();
+        write_to_stdout_ignore_sigpipe(output.join("\n").as_bytes())
+          .map_err(|err| err.into())
+      }
+    } else if let Some(why) = maybe_why {
+      if dot {
+        return Err(generic_error(
+          "--why does not support the \"dot\" format.",
+        ));
+      }
+      let target = resolve_url_or_path(&why)?;
+      if !info.modules.iter().any(|m| m.specifier == target) {
+        return Err(generic_error(format!(
+          "Module \"{}\" is not part of the module graph of \"{}\".",
+          target, specifier
+        )));
+      }
+      let chains = info.why(&target);
+      if json {
+        write_json_to_stdout(&json!(chains))
+      } else {
+        write_to_stdout_ignore_sigpipe(chains.to_string().as_bytes())
+          .map_err(|err| err.into())
+      }
+    } else if dot {
+      write_to_stdout_ignore_sigpipe(info.to_dot().as_bytes())
+        .map_err(|err| err.into())
+    } else if json {
       write_json_to_stdout(&json!(info))
     } else {
       write_to_stdout_ignore_sigpipe(info.to_string().as_bytes())
@@ -418,6 +505,31 @@ async fn install_command(
   tools::installer::install(flags, &module_url, args, name, root, force)
 }
 
+async fn install_list_command(root: Option<PathBuf>) -> Result<(), AnyError> {
+  tools::installer::list(root)
+}
+
+async fn install_upgrade_command(
+  flags: Flags,
+  root: Option<PathBuf>,
+) -> Result<(), AnyError> {
+  // The URLs of the installed scripts have to be resolved again, instead of
+  // being read from the cache.
+  let program_state = ProgramState::build(Flags {
+    reload: true,
+    ..flags
+  })
+  .await?;
+  tools::installer::upgrade(&program_state.file_fetcher, root).await
+}
+
+async fn uninstall_command(
+  name: String,
+  root: Option<PathBuf>,
+) -> Result<(), AnyError> {
+  tools::installer::uninstall(name, root)
+}
+
 async fn lsp_command() -> Result<(), AnyError> {
   lsp::start().await
 }
@@ -444,6 +556,9 @@ async fn lint_command(
 async fn cache_command(
   flags: Flags,
   files: Vec<String>,
+  list: bool,
+  gc: bool,
+  max_age: Option<u64>,
 ) -> Result<(), AnyError> {
   let lib = if flags.unstable {
     module_graph::TypeLib::UnstableDenoWindow
@@ -451,6 +566,63 @@ async fn cache_command(
     module_graph::TypeLib::DenoWindow
   };
   let program_state = ProgramState::build(flags).await?;
+  let http_cache = http_cache::HttpCache::new(
+    &program_state.file_fetcher.get_http_cache_location(),
+  );
+
+  if list {
+    let entries = tools::cache::list(&http_cache)?;
+    let mut total_size = 0;
+    for entry in entries.iter() {
+      total_size += entry.size;
+      print!("{}", entry);
+    }
+    println!(
+      "{} {} module(s), {}",
+      colors::bold("total:"),
+      entries.len(),
+      info::human_size(total_size as f64)
+    );
+    return Ok(());
+  }
+
+  if gc {
+    let maybe_reachable = if files.is_empty() {
+      None
+    } else {
+      let handler = Arc::new(Mutex::new(FetchHandler::new(
+        &program_state,
+        Permissions::allow_all(),
+      )?));
+      let mut builder = module_graph::GraphBuilder::new(
+        handler,
+        program_state.maybe_import_map.clone(),
+        program_state.lockfile.clone(),
+      );
+      for file in files {
+        let specifier = resolve_url_or_path(&file)?;
+        builder.add(&specifier, false).await?;
+      }
+      let graph = builder.get_graph();
+      Some(graph.get_specifiers().into_iter().collect())
+    };
+    let max_age = match max_age {
+      Some(days) => Some(std::time::Duration::from_secs(
+        days
+          .checked_mul(60 * 60 * 24)
+          .ok_or_else(|| generic_error("--max-age is too large"))?,
+      )),
+      None => None,
+    };
+    let stats = tools::cache::gc(
+      &http_cache,
+      &program_state.dir.gen_cache,
+      maybe_reachable.as_ref(),
+      max_age,
+    )?;
+    println!("{}", stats);
+    return Ok(());
+  }
 
   for file in files {
     let specifier = resolve_url_or_path(&file)?;
@@ -516,7 +688,7 @@ async fn eval_command(
 }
 
 async fn create_module_graph_and_maybe_check(
-  module_specifier: ModuleSpecifier,
+  module_specifiers: Vec<ModuleSpecifier>,
   program_state: Arc<ProgramState>,
   debug: bool,
 ) -> Result<module_graph::Graph, AnyError> {
@@ -531,7 +703,9 @@ async fn create_module_graph_and_maybe_check(
     program_state.maybe_import_map.clone(),
     program_state.lockfile.clone(),
   );
-  builder.add(&module_specifier, false).await?;
+  for module_specifier in module_specifiers.iter() {
+    builder.add(module_specifier, false).await?;
+  }
   let module_graph = builder.get_graph();
 
   if !program_state.flags.no_check {
@@ -582,10 +756,32 @@ fn bundle_module_graph(
   Ok(bundle)
 }
 
+fn bundle_module_graph_chunks(
+  module_graph: module_graph::Graph,
+  flags: Flags,
+  debug: bool,
+) -> Result<Vec<module_graph::BundleChunk>, AnyError> {
+  let (chunks, stats, maybe_ignored_options) =
+    module_graph.bundle_chunks(module_graph::BundleOptions {
+      debug,
+      maybe_config_path: flags.config_path,
+    })?;
+  match maybe_ignored_options {
+    Some(ignored_options) if flags.no_check => {
+      eprintln!("{}", ignored_options);
+    }
+    _ => {}
+  }
+  debug!("{}", stats);
+  Ok(chunks)
+}
+
 async fn bundle_command(
   flags: Flags,
   source_file: String,
   out_file: Option<PathBuf>,
+  out_dir: Option<PathBuf>,
+  entrypoints: Vec<String>,
 ) -> Result<(), AnyError> {
   let debug = flags.log_level == Some(log::Level::Debug);
 
@@ -593,20 +789,26 @@ async fn bundle_command(
     let flags = flags.clone();
     let source_file1 = source_file.clone();
     let source_file2 = source_file.clone();
+    let entrypoints = entrypoints.clone();
     async move {
-      let module_specifier = resolve_url_or_path(&source_file1)?;
+      let mut module_specifiers = vec![resolve_url_or_path(&source_file1)?];
+      for entrypoint in entrypoints.iter() {
+        module_specifiers.push(resolve_url_or_path(entrypoint)?);
+      }
 
       debug!(">>>>> bundle START");
       let program_state = ProgramState::build(flags.clone()).await?;
 
-      info!(
-        "{} {}",
-        colors::green("Bundle"),
-        module_specifier.to_string()
-      );
+      for module_specifier in module_specifiers.iter() {
+        info!(
+          "{} {}",
+          colors::green("Bundle"),
+          module_specifier.to_string()
+        );
+      }
 
       let module_graph = create_module_graph_and_maybe_check(
-        module_specifier,
+        module_specifiers,
         program_state.clone(),
         debug,
       )
@@ -641,7 +843,29 @@ async fn bundle_command(
   let operation = |module_graph: module_graph::Graph| {
     let flags = flags.clone();
     let out_file = out_file.clone();
+    let out_dir = out_dir.clone();
     async move {
+      if let Some(out_dir) = out_dir.as_ref() {
+        let chunks = bundle_module_graph_chunks(module_graph, flags, debug)?;
+
+        debug!(">>>>> bundle END");
+
+        std::fs::create_dir_all(out_dir)?;
+        for chunk in chunks.iter() {
+          let chunk_path = out_dir.join(&chunk.file_name);
+          let map_path = out_dir.join(format!("{}.map", chunk.file_name));
+          fs_util::write_file(&chunk_path, chunk.code.as_bytes(), 0o644)?;
+          fs_util::write_file(&map_path, chunk.map.as_bytes(), 0o644)?;
+          info!(
+            "{} {:?} ({})",
+            colors::green("Emit"),
+            chunk_path,
+            colors::gray(&info::human_size(chunk.code.len() as f64))
+          );
+        }
+        return Ok(());
+      }
+
       let output = bundle_module_graph(module_graph, flags, debug)?;
 
       debug!(">>>>> bundle END");
@@ -708,15 +932,21 @@ async fn format_command(
   Ok(())
 }
 
-async fn run_repl(flags: Flags) -> Result<(), AnyError> {
+async fn run_repl(
+  flags: Flags,
+  maybe_eval_files: Option<Vec<String>>,
+) -> Result<(), AnyError> {
   let main_module = resolve_url_or_path("./$deno$repl.ts").unwrap();
   let permissions = Permissions::from_options(&flags.clone().into());
   let program_state = ProgramState::build(flags).await?;
-  let mut worker =
-    create_main_worker(&program_state, main_module.clone(), permissions);
+  let create_worker = || {
+    create_main_worker(&program_state, main_module.clone(), permissions.clone())
+  };
+  let mut worker = create_worker();
   worker.run_event_loop().await?;
 
-  tools::repl::run(&program_state, worker).await
+  tools::repl::run(&program_state, worker, &create_worker, maybe_eval_files)
+    .await
 }
 
 async fn run_from_stdin(flags: Flags) -> Result<(), AnyError> {
@@ -1055,7 +1285,10 @@ fn get_subcommand(
     DenoSubcommand::Bundle {
       source_file,
       out_file,
-    } => bundle_command(flags, source_file, out_file).boxed_local(),
+      out_dir,
+      entrypoints,
+    } => bundle_command(flags, source_file, out_file, out_dir, entrypoints)
+      .boxed_local(),
     DenoSubcommand::Doc {
       source_file,
       json,
@@ -1065,17 +1298,33 @@ fn get_subcommand(
     DenoSubcommand::Eval { print, code, ext } => {
       eval_command(flags, code, ext, print).boxed_local()
     }
-    DenoSubcommand::Cache { files } => {
-      cache_command(flags, files).boxed_local()
-    }
+    DenoSubcommand::Cache {
+      files,
+      list,
+      gc,
+      max_age,
+    } => cache_command(flags, files, list, gc, max_age).boxed_local(),
     DenoSubcommand::Compile {
       source_file,
       output,
       args,
       lite,
       target,
-    } => compile_command(flags, source_file, output, args, target, lite)
-      .boxed_local(),
+      entrypoints,
+      include,
+      snapshot,
+    } => compile_command(
+      flags,
+      source_file,
+      output,
+      args,
+      target,
+      lite,
+      entrypoints,
+      include,
+      snapshot,
+    )
+    .boxed_local(),
     DenoSubcommand::Coverage {
       files,
       ignore,
@@ -1090,9 +1339,13 @@ fn get_subcommand(
       ignore,
       ext,
     } => format_command(flags, files, ignore, check, ext).boxed_local(),
-    DenoSubcommand::Info { file, json } => {
-      info_command(flags, file, json).boxed_local()
-    }
+    DenoSubcommand::Info {
+      file,
+      json,
+      dupes,
+      why,
+      dot,
+    } => info_command(flags, file, json, dupes, why, dot).boxed_local(),
     DenoSubcommand::Install {
       module_url,
       args,
@@ -1102,6 +1355,12 @@ fn get_subcommand(
     } => {
       install_command(flags, module_url, args, name, root, force).boxed_local()
     }
+    DenoSubcommand::InstallList { root } => {
+      install_list_command(root).boxed_local()
+    }
+    DenoSubcommand::InstallUpgrade { root } => {
+      install_upgrade_command(flags, root).boxed_local()
+    }
     DenoSubcommand::Lsp => lsp_command().boxed_local(),
     DenoSubcommand::Lint {
       files,
@@ -1109,7 +1368,9 @@ fn get_subcommand(
       ignore,
       json,
     } => lint_command(flags, files, rules, ignore, json).boxed_local(),
-    DenoSubcommand::Repl => run_repl(flags).boxed_local(),
+    DenoSubcommand::Repl { eval_files } => {
+      run_repl(flags, eval_files).boxed_local()
+    }
     DenoSubcommand::Run { script } => run_command(flags, script).boxed_local(),
     DenoSubcommand::Test {
       no_run,
@@ -1137,6 +1398,9 @@ fn get_subcommand(
       }
       std::process::exit(0);
     }
+    DenoSubcommand::Uninstall { name, root } => {
+      uninstall_command(name, root).boxed_local()
+    }
     DenoSubcommand::Upgrade {
       force,
       dry_run,
@@ -1144,8 +1408,10 @@ fn get_subcommand(
       version,
       output,
       ca_file,
+      mirror,
+      from_file,
     } => tools::upgrade::upgrade_command(
-      dry_run, force, canary, version, output, ca_file,
+      dry_run, force, canary, version, output, ca_file, mirror, from_file,
     )
     .boxed_local(),
   }
@@ -1173,8 +1439,8 @@ pub fn main() {
 
   let args: Vec<String> = env::args().collect();
   let standalone_res = match standalone::extract_standalone(args.clone()) {
-    Ok(Some((metadata, bundle))) => {
-      tokio_util::run_basic(standalone::run(bundle, metadata))
+    Ok(Some((metadata, modules))) => {
+      tokio_util::run_basic(standalone::run(modules, metadata))
     }
     Ok(None) => Ok(()),
     Err(err) => Err(err),
@@ -6,6 +6,8 @@
 extern crate lazy_static;
 
 mod colors;
+mod fmt_errors;
+mod source_maps;
 mod standalone;
 mod tokio_util;
 mod version;
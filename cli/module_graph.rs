@@ -52,6 +52,8 @@ use std::result;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::time::Instant;
+use swc_ecmascript::visit::Fold;
+use swc_ecmascript::visit::FoldWith;
 
 lazy_static! {
   /// Matched the `@deno-types` pragma.
@@ -124,6 +126,7 @@ struct BundleLoader<'a> {
   emit_options: &'a ast::EmitOptions,
   globals: &'a swc_common::Globals,
   graph: &'a Graph,
+  maybe_chunks: Option<&'a ChunkMap>,
 }
 
 impl<'a> BundleLoader<'a> {
@@ -132,12 +135,14 @@ impl<'a> BundleLoader<'a> {
     emit_options: &'a ast::EmitOptions,
     globals: &'a swc_common::Globals,
     cm: Rc<swc_common::SourceMap>,
+    maybe_chunks: Option<&'a ChunkMap>,
   ) -> Self {
     BundleLoader {
       cm,
       emit_options,
       globals,
       graph,
+      maybe_chunks,
     }
   }
 }
@@ -156,7 +161,7 @@ impl swc_bundler::Load for BundleLoader<'_> {
             .graph
             .get_media_type(&specifier)
             .context("Looking up media type during bundling.")?;
-          let (source_file, module) = transpile_module(
+          let (source_file, mut module) = transpile_module(
             filename,
             &src,
             &media_type,
@@ -164,6 +169,13 @@ impl swc_bundler::Load for BundleLoader<'_> {
             self.globals,
             self.cm.clone(),
           )?;
+          if let Some(chunks) = self.maybe_chunks {
+            module = module.fold_with(&mut ChunkImportRewriter {
+              chunks,
+              graph: self.graph,
+              referrer: &specifier,
+            });
+          }
           Ok(swc_bundler::ModuleData {
             fm: source_file,
             module,
@@ -181,6 +193,93 @@ impl swc_bundler::Load for BundleLoader<'_> {
   }
 }
 
+/// Maps the first module of every chunk of a split bundle to the file name of
+/// the chunk.
+struct ChunkMap {
+  /// The first module of the chunk which is currently being bundled.
+  current: ModuleSpecifier,
+  names: HashMap<ModuleSpecifier, String>,
+}
+
+/// Rewrites the imports of modules which start another chunk to import that
+/// chunk instead, so swc leaves them in place as external modules.
+struct ChunkImportRewriter<'a> {
+  chunks: &'a ChunkMap,
+  graph: &'a Graph,
+  referrer: &'a ModuleSpecifier,
+}
+
+impl ChunkImportRewriter<'_> {
+  fn rewrite(&self, src: &mut swc_ecmascript::ast::Str, is_dynamic: bool) {
+    let resolved = match self.graph.resolve(&src.value, self.referrer, false) {
+      Ok(resolved) => resolved,
+      Err(_) => return,
+    };
+    // A static import of the first module of the current chunk is part of the
+    // chunk itself.
+    if !is_dynamic && resolved == self.chunks.current {
+      return;
+    }
+    if let Some(name) = self.chunks.names.get(&resolved) {
+      src.value = format!("./{}", name).into();
+      src.has_escape = false;
+    }
+  }
+}
+
+impl Fold for ChunkImportRewriter<'_> {
+  fn fold_import_decl(
+    &mut self,
+    mut decl: swc_ecmascript::ast::ImportDecl,
+  ) -> swc_ecmascript::ast::ImportDecl {
+    self.rewrite(&mut decl.src, false);
+    decl
+  }
+
+  fn fold_named_export(
+    &mut self,
+    mut export: swc_ecmascript::ast::NamedExport,
+  ) -> swc_ecmascript::ast::NamedExport {
+    if let Some(src) = export.src.as_mut() {
+      self.rewrite(src, false);
+    }
+    export
+  }
+
+  fn fold_export_all(
+    &mut self,
+    mut export: swc_ecmascript::ast::ExportAll,
+  ) -> swc_ecmascript::ast::ExportAll {
+    self.rewrite(&mut export.src, false);
+    export
+  }
+
+  fn fold_call_expr(
+    &mut self,
+    call: swc_ecmascript::ast::CallExpr,
+  ) -> swc_ecmascript::ast::CallExpr {
+    use swc_ecmascript::ast::Expr;
+    use swc_ecmascript::ast::ExprOrSuper;
+    use swc_ecmascript::ast::Lit;
+
+    let mut call = call.fold_children_with(self);
+    let is_import = match &call.callee {
+      ExprOrSuper::Expr(callee) => {
+        matches!(&**callee, Expr::Ident(ident) if &*ident.sym == "import")
+      }
+      _ => false,
+    };
+    if is_import {
+      if let Some(arg) = call.args.first_mut() {
+        if let Expr::Lit(Lit::Str(src)) = &mut *arg.expr {
+          self.rewrite(src, true);
+        }
+      }
+    }
+    call
+  }
+}
+
 /// An enum which represents the parsed out values of references in source code.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum TypeScriptReference {
@@ -628,6 +727,34 @@ impl Default for BundleType {
   }
 }
 
+/// The reason a module starts its own chunk when splitting a bundle.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ChunkKind {
+  /// The chunk of one of the root modules of the graph.
+  Entry,
+  /// A chunk which is loaded by a dynamic import.
+  Dynamic,
+  /// A chunk which contains modules that are statically imported from more
+  /// than one other chunk.
+  Shared,
+}
+
+/// A single output file of a bundle which has been split into chunks.
+#[derive(Debug)]
+pub struct BundleChunk {
+  /// The first module of the chunk, which is the module whose exports are the
+  /// exports of the chunk.
+  pub specifier: ModuleSpecifier,
+  pub kind: ChunkKind,
+  /// The file name of the chunk, relative to the output directory. Chunks
+  /// import each other using this name.
+  pub file_name: String,
+  pub code: String,
+  /// The source map of the chunk, which is referenced from the code as
+  /// `<file_name>.map`.
+  pub map: String,
+}
+
 #[derive(Debug, Default)]
 pub struct EmitOptions {
   /// If true, then code will be type checked, otherwise type checking will be
@@ -643,6 +770,17 @@ pub struct EmitOptions {
   pub maybe_user_config: Option<HashMap<String, Value>>,
 }
 
+/// A module of the graph in the form it is loaded at runtime.
+#[derive(Debug, Clone)]
+pub struct RuntimeModule {
+  pub specifier: ModuleSpecifier,
+  pub code: String,
+  pub maybe_map: Option<String>,
+  /// The runtime code dependencies of the module, mapping the specifiers as
+  /// written in the source to the modules in the graph they resolve to.
+  pub dependencies: HashMap<String, ModuleSpecifier>,
+}
+
 /// A structure which provides options when transpiling modules.
 #[derive(Debug, Default)]
 pub struct TranspileOptions {
@@ -770,19 +908,11 @@ impl Graph {
 
     let start = Instant::now();
     let root_specifier = self.roots[0].clone();
-    let mut ts_config = TsConfig::new(json!({
-      "checkJs": false,
-      "emitDecoratorMetadata": false,
-      "inlineSourceMap": true,
-      "jsx": "react",
-      "jsxFactory": "React.createElement",
-      "jsxFragmentFactory": "React.Fragment",
-    }));
-    let maybe_ignored_options =
-      ts_config.merge_tsconfig(options.maybe_config_path)?;
+    let (emit_options, maybe_ignored_options) =
+      get_bundle_emit_options(options.maybe_config_path)?;
 
     let s =
-      self.emit_bundle(&root_specifier, &ts_config.into(), &BundleType::Esm)?;
+      self.emit_bundle(&root_specifier, &emit_options, &BundleType::Esm)?;
     let stats = Stats(vec![
       ("Files".to_string(), self.modules.len() as u32),
       ("Total time".to_string(), start.elapsed().as_millis() as u32),
@@ -791,6 +921,75 @@ impl Graph {
     Ok((s, stats, maybe_ignored_options))
   }
 
+  /// Transform the module graph into a set of JavaScript modules, one for
+  /// every root module of the graph and every dynamically imported module,
+  /// plus shared chunks for modules which are statically imported from more
+  /// than one of those.  Each chunk comes with its own source map.
+  pub fn bundle_chunks(
+    &self,
+    options: BundleOptions,
+  ) -> Result<(Vec<BundleChunk>, Stats, Option<IgnoredCompilerOptions>), AnyError>
+  {
+    if self.roots.is_empty() {
+      return Err(
+        GraphError::NotSupported(
+          "Bundling requires at least one root module in the graph."
+            .to_string(),
+        )
+        .into(),
+      );
+    }
+
+    let start = Instant::now();
+    let (emit_options, maybe_ignored_options) =
+      get_bundle_emit_options(options.maybe_config_path)?;
+
+    let entries = self.get_chunk_entries();
+    let mut taken = HashSet::new();
+    let names: HashMap<ModuleSpecifier, String> = entries
+      .iter()
+      .map(|(specifier, kind)| {
+        (
+          specifier.clone(),
+          get_chunk_file_name(specifier, *kind, &mut taken),
+        )
+      })
+      .collect();
+
+    let mut chunks = Vec::new();
+    for (specifier, kind) in entries {
+      let chunk_map = ChunkMap {
+        current: specifier.clone(),
+        names: names.clone(),
+      };
+      let file_name = names.get(&specifier).unwrap().clone();
+      let (mut code, maybe_map) = self.emit_bundle_with_chunks(
+        &specifier,
+        &emit_options,
+        &BundleType::Esm,
+        Some(&chunk_map),
+      )?;
+      if !code.ends_with('\n') {
+        code.push('\n');
+      }
+      code.push_str(&format!("//# sourceMappingURL={}.map\n", file_name));
+      chunks.push(BundleChunk {
+        specifier,
+        kind,
+        file_name,
+        code,
+        map: maybe_map.unwrap(),
+      });
+    }
+    let stats = Stats(vec![
+      ("Files".to_string(), self.modules.len() as u32),
+      ("Chunks".to_string(), chunks.len() as u32),
+      ("Total time".to_string(), start.elapsed().as_millis() as u32),
+    ]);
+
+    Ok((chunks, stats, maybe_ignored_options))
+  }
+
   /// Type check the module graph, corresponding to the options provided.
   pub fn check(self, options: CheckOptions) -> Result<ResultInfo, AnyError> {
     self.validate()?;
@@ -1116,17 +1315,46 @@ impl Graph {
     emit_options: &ast::EmitOptions,
     bundle_type: &BundleType,
   ) -> Result<String, AnyError> {
+    let (s, _) = self.emit_bundle_with_chunks(
+      specifier,
+      emit_options,
+      bundle_type,
+      None,
+    )?;
+    Ok(s)
+  }
+
+  /// Bundle the modules reachable from `specifier`.  When a chunk map is
+  /// provided, the modules which start other chunks are left as imports of
+  /// those chunks and a source map for the output is returned as well.
+  fn emit_bundle_with_chunks(
+    &self,
+    specifier: &ModuleSpecifier,
+    emit_options: &ast::EmitOptions,
+    bundle_type: &BundleType,
+    maybe_chunks: Option<&ChunkMap>,
+  ) -> Result<(String, Option<String>), AnyError> {
     let cm = Rc::new(swc_common::SourceMap::new(
       swc_common::FilePathMapping::empty(),
     ));
     let globals = swc_common::Globals::new();
-    let loader = BundleLoader::new(self, emit_options, &globals, cm.clone());
+    let loader =
+      BundleLoader::new(self, emit_options, &globals, cm.clone(), maybe_chunks);
     let hook = Box::new(BundleHook);
     let module = match bundle_type {
       BundleType::Esm => swc_bundler::ModuleType::Es,
       BundleType::Iife => swc_bundler::ModuleType::Iife,
       _ => unreachable!("invalid bundle type"),
     };
+    let external_modules = maybe_chunks
+      .map(|chunks| {
+        chunks
+          .names
+          .values()
+          .map(|name| format!("./{}", name).into())
+          .collect()
+      })
+      .unwrap_or_default();
     let bundler = swc_bundler::Bundler::new(
       &globals,
       cm.clone(),
@@ -1134,6 +1362,7 @@ impl Graph {
       self,
       swc_bundler::Config {
         module,
+        external_modules,
         ..Default::default()
       },
       hook,
@@ -1147,13 +1376,21 @@ impl Graph {
       .bundle(entries)
       .context("Unable to output bundle during Graph::bundle().")?;
     let mut buf = Vec::new();
+    let mut src_map_buf = Vec::new();
     {
       let mut emitter = swc_ecmascript::codegen::Emitter {
         cfg: swc_ecmascript::codegen::Config { minify: false },
         cm: cm.clone(),
         comments: None,
         wr: Box::new(swc_ecmascript::codegen::text_writer::JsWriter::new(
-          cm, "\n", &mut buf, None,
+          cm.clone(),
+          "\n",
+          &mut buf,
+          if maybe_chunks.is_some() {
+            Some(&mut src_map_buf)
+          } else {
+            None
+          },
         )),
       };
 
@@ -1161,8 +1398,140 @@ impl Graph {
         .emit_module(&output[0].module)
         .context("Unable to emit bundle during Graph::bundle().")?;
     }
+    let s = String::from_utf8(buf)
+      .context("Emitted bundle is an invalid utf-8 string.")?;
+
+    let maybe_map = if maybe_chunks.is_some() {
+      let mut buf = Vec::new();
+      cm.build_source_map_from(&mut src_map_buf, None)
+        .to_writer(&mut buf)?;
+      Some(String::from_utf8(buf)?)
+    } else {
+      None
+    };
+
+    Ok((s, maybe_map))
+  }
+
+  /// Determine the modules which start a chunk when splitting the graph.
+  ///
+  /// Every root module and every dynamically imported module starts a chunk.
+  /// The modules which are statically imported from more than one chunk are
+  /// then moved into shared chunks, until every module belongs to exactly one
+  /// chunk.  The roots are returned first, followed by the other chunks sorted
+  /// by specifier.
+  fn get_chunk_entries(&self) -> Vec<(ModuleSpecifier, ChunkKind)> {
+    let mut entries: HashMap<ModuleSpecifier, ChunkKind> = HashMap::new();
+    for root in self.roots.iter() {
+      entries.insert(self.resolve_specifier(root).clone(), ChunkKind::Entry);
+    }
+
+    let mut seen = HashSet::new();
+    let mut pending: Vec<ModuleSpecifier> = entries.keys().cloned().collect();
+    while let Some(specifier) = pending.pop() {
+      if !seen.insert(specifier.clone()) {
+        continue;
+      }
+      for (dep, is_dynamic) in self.get_code_dependencies(&specifier) {
+        if is_dynamic {
+          entries.entry(dep.clone()).or_insert(ChunkKind::Dynamic);
+        }
+        pending.push(dep);
+      }
+    }
+
+    loop {
+      // The chunks which statically reach each module, and the modules which
+      // statically import it, without crossing into other chunks.
+      let mut reached_by: HashMap<ModuleSpecifier, HashSet<ModuleSpecifier>> =
+        HashMap::new();
+      let mut importers: HashMap<ModuleSpecifier, Vec<ModuleSpecifier>> =
+        HashMap::new();
+      for entry in entries.keys() {
+        let mut seen = HashSet::new();
+        let mut pending = vec![entry.clone()];
+        while let Some(specifier) = pending.pop() {
+          if !seen.insert(specifier.clone()) {
+            continue;
+          }
+          reached_by
+            .entry(specifier.clone())
+            .or_default()
+            .insert(entry.clone());
+          for (dep, is_dynamic) in self.get_code_dependencies(&specifier) {
+            if is_dynamic || entries.contains_key(&dep) {
+              continue;
+            }
+            importers
+              .entry(dep.clone())
+              .or_default()
+              .push(specifier.clone());
+            pending.push(dep);
+          }
+        }
+      }
+
+      // A module shared between chunks gets its own chunk when one of its
+      // importers is reached by a different set of chunks, as otherwise the
+      // importer is part of the same shared chunk.
+      let shared: Vec<ModuleSpecifier> = reached_by
+        .iter()
+        .filter(|(specifier, chunks)| {
+          chunks.len() > 1
+            && importers
+              .get(*specifier)
+              .map(|importers| {
+                importers.iter().any(|i| reached_by.get(i) != Some(*chunks))
+              })
+              .unwrap_or(false)
+        })
+        .map(|(specifier, _)| specifier.clone())
+        .collect();
+      if shared.is_empty() {
+        break;
+      }
+      for specifier in shared {
+        entries.insert(specifier, ChunkKind::Shared);
+      }
+    }
 
-    String::from_utf8(buf).context("Emitted bundle is an invalid utf-8 string.")
+    let mut roots: Vec<(ModuleSpecifier, ChunkKind)> = Vec::new();
+    for root in self.roots.iter() {
+      let root = self.resolve_specifier(root);
+      if let Some(kind) = entries.remove(root) {
+        roots.push((root.clone(), kind));
+      }
+    }
+    let mut others: Vec<(ModuleSpecifier, ChunkKind)> =
+      entries.into_iter().collect();
+    others.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+    roots.extend(others);
+    roots
+  }
+
+  /// Return the runtime code dependencies of a module, flagging the ones which
+  /// are dynamically imported.  Type only dependencies are ignored.
+  fn get_code_dependencies(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Vec<(ModuleSpecifier, bool)> {
+    let module = match self.get_module(specifier) {
+      ModuleSlot::Module(module) => module,
+      _ => return Vec::new(),
+    };
+    let mut deps: Vec<(ModuleSpecifier, bool)> = Vec::new();
+    for dep in module.dependencies.values() {
+      if let Some(code) = &dep.maybe_code {
+        let code = self.resolve_specifier(code);
+        if let ModuleSlot::Module(m) = self.get_module(code) {
+          if m.media_type != MediaType::Dts && m.media_type != MediaType::Json {
+            deps.push((code.clone(), dep.is_dynamic));
+          }
+        }
+      }
+    }
+    deps.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+    deps
   }
 
   /// Update the handler with any modules that are marked as _dirty_ and update
@@ -1246,12 +1615,84 @@ impl Graph {
     self.modules.get_mut(s)
   }
 
+  /// Return the redirects which were followed while building the graph.
+  pub fn get_redirects(&self) -> HashMap<ModuleSpecifier, ModuleSpecifier> {
+    self.redirects.clone()
+  }
+
+  /// Return every module of the graph which can be loaded at runtime, with
+  /// TypeScript and JSX transpiled to JavaScript.  Type only modules are
+  /// skipped.  This is used to embed the graph in standalone binaries.
+  pub fn get_runtime_modules(
+    &self,
+    maybe_config_path: Option<String>,
+  ) -> Result<(Vec<RuntimeModule>, Option<IgnoredCompilerOptions>), AnyError>
+  {
+    let mut ts_config = TsConfig::new(json!({
+      "checkJs": false,
+      "emitDecoratorMetadata": false,
+      "inlineSourceMap": false,
+      "jsx": "react",
+      "jsxFactory": "React.createElement",
+      "jsxFragmentFactory": "React.Fragment",
+    }));
+    let maybe_ignored_options = ts_config.merge_tsconfig(maybe_config_path)?;
+    let emit_options: ast::EmitOptions = ts_config.into();
+
+    let mut modules = Vec::new();
+    for module_slot in self.modules.values() {
+      let module = match module_slot {
+        ModuleSlot::Module(module) => module,
+        _ => continue,
+      };
+      let (code, maybe_map) = match module.media_type {
+        MediaType::JavaScript => (module.source.clone(), None),
+        MediaType::JSX | MediaType::TypeScript | MediaType::TSX => parse(
+          module.specifier.as_str(),
+          &module.source,
+          &module.media_type,
+        )?
+        .transpile(&emit_options)?,
+        _ => continue,
+      };
+      let dependencies = module
+        .dependencies
+        .iter()
+        .filter_map(|(specifier, dep)| {
+          dep.maybe_code.as_ref().map(|code| {
+            (specifier.clone(), self.resolve_specifier(code).clone())
+          })
+        })
+        .collect();
+      modules.push(RuntimeModule {
+        specifier: module.specifier.clone(),
+        code,
+        maybe_map,
+        dependencies,
+      });
+    }
+    modules.sort_by(|a, b| a.specifier.as_str().cmp(b.specifier.as_str()));
+
+    Ok((modules, maybe_ignored_options))
+  }
+
   /// Consume graph and return list of all module specifiers contained in the
   /// graph.
   pub fn get_modules(&self) -> Vec<ModuleSpecifier> {
     self.modules.keys().map(|s| s.to_owned()).collect()
   }
 
+  /// Return all module specifiers contained in the graph, including the
+  /// specifiers which were redirected to another module.
+  pub fn get_specifiers(&self) -> Vec<ModuleSpecifier> {
+    self
+      .modules
+      .keys()
+      .chain(self.redirects.keys())
+      .map(|s| s.to_owned())
+      .collect()
+  }
+
   /// Transform `self.roots` into something that works for `tsc`, because `tsc`
   /// doesn't like root names without extensions that match its expectations,
   /// nor does it have any concept of redirection, so we have to resolve all
@@ -1717,6 +2158,50 @@ impl swc_bundler::Resolve for Graph {
   }
 }
 
+/// The emit options used when bundling, based on an optional user supplied
+/// configuration file.
+fn get_bundle_emit_options(
+  maybe_config_path: Option<String>,
+) -> Result<(ast::EmitOptions, Option<IgnoredCompilerOptions>), AnyError> {
+  let mut ts_config = TsConfig::new(json!({
+    "checkJs": false,
+    "emitDecoratorMetadata": false,
+    "inlineSourceMap": true,
+    "jsx": "react",
+    "jsxFactory": "React.createElement",
+    "jsxFragmentFactory": "React.Fragment",
+  }));
+  let maybe_ignored_options = ts_config.merge_tsconfig(maybe_config_path)?;
+  Ok((ts_config.into(), maybe_ignored_options))
+}
+
+/// Derive the file name of a chunk from the first module of the chunk.  The
+/// chunks of root modules keep the name of the module where possible, other
+/// chunks get a hash of the specifier appended so they are unique.
+fn get_chunk_file_name(
+  specifier: &ModuleSpecifier,
+  kind: ChunkKind,
+  taken: &mut HashSet<String>,
+) -> String {
+  let stem = specifier
+    .path_segments()
+    .and_then(|mut segments| segments.next_back())
+    .and_then(|name| name.split('.').next())
+    .filter(|stem| !stem.is_empty())
+    .unwrap_or("chunk");
+  let hash = checksum::gen(&[specifier.as_str().as_bytes()]);
+  let mut name = if kind == ChunkKind::Entry {
+    format!("{}.js", stem)
+  } else {
+    format!("{}-{}.js", stem, &hash[..8])
+  };
+  if taken.contains(&name) {
+    name = format!("{}-{}.js", stem, &hash[..8]);
+  }
+  taken.insert(name.clone());
+  name
+}
+
 /// A structure for building a dependency graph of modules.
 pub struct GraphBuilder {
   graph: Graph,
@@ -2156,6 +2641,85 @@ pub mod tests {
     }
   }
 
+  #[tokio::test]
+  async fn test_graph_bundle_chunks() {
+    let sources: HashMap<String, String> = map!(
+      "/a.ts" => r#"
+        import { shared } from "./shared.ts";
+        console.log(shared);
+        import("./lazy.ts").then((lazy) => lazy.run());
+      "#,
+      "/b.ts" => r#"
+        import { shared } from "./shared.ts";
+        export const b = shared + 1;
+      "#,
+      "/shared.ts" => r#"
+        import { util } from "./util.ts";
+        export const shared = util();
+      "#,
+      "/util.ts" => r#"
+        export function util() {
+          return 1;
+        }
+      "#,
+      "/lazy.ts" => r#"
+        import { util } from "./util.ts";
+        export function run() {
+          return util();
+        }
+      "#
+    )
+    .iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect();
+    let handler = Arc::new(Mutex::new(MemoryHandler::new(sources)));
+    let mut builder = GraphBuilder::new(handler, None, None);
+    for specifier in &["file:///a.ts", "file:///b.ts"] {
+      let specifier = resolve_url_or_path(specifier).unwrap();
+      builder
+        .add(&specifier, false)
+        .await
+        .expect("module not inserted");
+    }
+    let graph = builder.get_graph();
+    assert!(graph.bundle(BundleOptions::default()).is_err());
+
+    let (chunks, stats, maybe_ignored_options) = graph
+      .bundle_chunks(BundleOptions::default())
+      .expect("could not bundle");
+    assert_eq!(stats.0.len(), 3);
+    assert_eq!(maybe_ignored_options, None);
+    let actual: Vec<(&str, ChunkKind)> = chunks
+      .iter()
+      .map(|c| (c.specifier.as_str(), c.kind))
+      .collect();
+    assert_eq!(
+      actual,
+      vec![
+        ("file:///a.ts", ChunkKind::Entry),
+        ("file:///b.ts", ChunkKind::Entry),
+        ("file:///lazy.ts", ChunkKind::Dynamic),
+        ("file:///shared.ts", ChunkKind::Shared),
+        ("file:///util.ts", ChunkKind::Shared),
+      ]
+    );
+    assert_eq!(chunks[0].file_name, "a.js");
+    assert_eq!(chunks[1].file_name, "b.js");
+    assert!(chunks[2].file_name.starts_with("lazy-"));
+    assert!(chunks[3].file_name.starts_with("shared-"));
+    assert!(chunks[4].file_name.starts_with("util-"));
+
+    let a = &chunks[0].code;
+    assert!(a.contains(&format!("from \"./{}\"", chunks[3].file_name)));
+    assert!(a.contains(&format!("import(\"./{}\")", chunks[2].file_name)));
+    assert!(!a.contains("function util"));
+    assert!(a.ends_with("//# sourceMappingURL=a.js.map\n"));
+    assert!(chunks[4].code.contains("function util"));
+    for chunk in chunks.iter() {
+      assert!(chunk.map.contains("\"version\":3"), "{}", chunk.file_name);
+    }
+  }
+
   #[tokio::test]
   async fn test_graph_check_emit() {
     let specifier = resolve_url_or_path("file:///tests/main.ts")
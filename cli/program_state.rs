@@ -14,6 +14,7 @@ use crate::module_graph::TypeLib;
 use crate::source_maps::SourceMapGetter;
 use crate::specifier_handler::FetchHandler;
 use crate::version;
+use deno_runtime::deno_fetch::Proxy;
 use deno_runtime::inspector::InspectorServer;
 use deno_runtime::permissions::Permissions;
 
@@ -54,6 +55,20 @@ pub struct ProgramState {
   pub maybe_import_map: Option<ImportMap>,
   pub maybe_inspector_server: Option<Arc<InspectorServer>>,
   pub ca_data: Option<Vec<u8>>,
+  pub ca_stores: Option<Vec<String>>,
+  pub proxy: Option<Proxy>,
+}
+
+/// Reads the comma separated list of certificate stores from
+/// `DENO_TLS_CA_STORE`.
+pub fn ca_stores_from_env() -> Option<Vec<String>> {
+  env::var("DENO_TLS_CA_STORE").ok().map(|ca_stores| {
+    ca_stores
+      .split(',')
+      .map(|store| store.trim().to_string())
+      .filter(|store| !store.is_empty())
+      .collect()
+  })
 }
 
 impl ProgramState {
@@ -67,6 +82,11 @@ impl ProgramState {
       Some(ca_file) => Some(read(ca_file).context("Failed to open ca file")?),
       None => None,
     };
+    let ca_stores = ca_stores_from_env();
+    let proxy = match &flags.proxy {
+      Some(proxy) => Some(Proxy::from_url(proxy)?),
+      None => None,
+    };
 
     let cache_usage = if flags.cached_only {
       CacheSetting::Only
@@ -74,6 +94,8 @@ impl ProgramState {
       CacheSetting::ReloadSome(flags.cache_blocklist.clone())
     } else if flags.reload {
       CacheSetting::ReloadAll
+    } else if flags.revalidate {
+      CacheSetting::RespectHeaders
     } else {
       CacheSetting::Use
     };
@@ -82,7 +104,9 @@ impl ProgramState {
       http_cache,
       cache_usage,
       !flags.no_remote,
+      ca_stores.clone(),
       ca_data.clone(),
+      proxy.clone(),
     )?;
 
     let lockfile = if let Some(filename) = &flags.lock {
@@ -133,6 +157,8 @@ impl ProgramState {
       maybe_import_map,
       maybe_inspector_server,
       ca_data,
+      ca_stores,
+      proxy,
     };
     Ok(Arc::new(program_state))
   }
@@ -592,6 +592,8 @@ pub mod tests {
       CacheSetting::Use,
       true,
       None,
+      None,
+      None,
     )
     .expect("could not setup");
     let disk_cache = deno_dir.gen_cache;
@@ -1,26 +1,44 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
 use crate::colors;
+use crate::fmt_errors::PrettyJsError;
+use crate::source_maps::apply_source_map;
+use crate::source_maps::get_orig_position;
+use crate::source_maps::CachedMaps;
+use crate::source_maps::SourceMapGetter;
 use crate::version;
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::error::Context;
 use deno_core::futures::FutureExt;
-use deno_core::resolve_url;
+use deno_core::resolve_import;
 use deno_core::serde::Deserialize;
 use deno_core::serde::Serialize;
 use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
 use deno_core::url::Url;
 use deno_core::v8_set_flags;
+use deno_core::JsErrorCreateFn;
+use deno_core::JsRuntime;
 use deno_core::ModuleLoader;
 use deno_core::ModuleSpecifier;
 use deno_core::OpState;
+use deno_core::Snapshot;
+use deno_core::ZeroCopyBuf;
+use deno_runtime::deno_fetch::Proxy;
+use deno_runtime::ops::fs::EmbeddedFiles;
+use deno_runtime::ops::worker_host::CreateWebWorkerCb;
 use deno_runtime::permissions::Permissions;
 use deno_runtime::permissions::PermissionsOptions;
+use deno_runtime::web_worker::WebWorker;
+use deno_runtime::web_worker::WebWorkerOptions;
 use deno_runtime::worker::MainWorker;
 use deno_runtime::worker::WorkerOptions;
 use log::Level;
 use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::convert::TryInto;
 use std::env::current_exe;
 use std::fs::File;
@@ -34,6 +52,7 @@ use std::sync::Arc;
 
 #[derive(Deserialize, Serialize)]
 pub struct Metadata {
+  pub main_module: Url,
   pub argv: Vec<String>,
   pub unstable: bool,
   pub seed: Option<u64>,
@@ -42,6 +61,54 @@ pub struct Metadata {
   pub v8_flags: Vec<String>,
   pub log_level: Option<Level>,
   pub ca_data: Option<Vec<u8>>,
+  /// The certificate stores from `DENO_TLS_CA_STORE` at compile time.
+  pub ca_stores: Option<Vec<String>>,
+  /// The URL passed to `--proxy`.
+  pub proxy: Option<String>,
+  /// Maps the path of each file included with `--include`, relative to the
+  /// compile root, to the offset and length of its contents in the binary.
+  pub files: HashMap<String, (u64, u64)>,
+  /// The offset and length of the V8 snapshot created with `--snapshot`.
+  pub snapshot: Option<(u64, u64)>,
+  /// The modules which were evaluated when the snapshot was taken. They can't
+  /// be loaded again by the runtime restored from the snapshot, as that would
+  /// evaluate them a second time.
+  pub snapshot_modules: Vec<String>,
+}
+
+/// A module embedded in a standalone binary.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct EmbeddedModule {
+  pub code: String,
+  /// The source map of modules transpiled from TypeScript or JSX.
+  pub map: Option<String>,
+  /// Maps the specifiers imported by the module to the embedded modules they
+  /// resolve to.
+  pub dependencies: HashMap<String, Url>,
+}
+
+/// The module graph embedded in a standalone binary. Modules are keyed by
+/// their specifier.
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct EmbeddedModules {
+  pub modules: HashMap<String, EmbeddedModule>,
+  pub redirects: HashMap<String, String>,
+}
+
+impl EmbeddedModules {
+  /// Look up a module, following redirects. Returns the final specifier of the
+  /// module together with the module.
+  fn get(&self, specifier: &Url) -> Option<(&str, &EmbeddedModule)> {
+    let mut specifier = specifier.as_str();
+    // Bound the number of redirects so a cycle can't loop forever.
+    for _ in 0..10 {
+      if let Some(module) = self.modules.get(specifier) {
+        return Some((specifier, module));
+      }
+      specifier = self.redirects.get(specifier)?;
+    }
+    None
+  }
 }
 
 pub const MAGIC_TRAILER: &[u8; 8] = b"d3n0l4nd";
@@ -50,14 +117,17 @@ pub const MAGIC_TRAILER: &[u8; 8] = b"d3n0l4nd";
 /// produced by `deno compile`. It determines if this is a standalone
 /// binary by checking for the magic trailer string `D3N0` at EOF-12.
 /// The magic trailer is followed by:
-/// - a u64 pointer to the JS bundle embedded in the binary
+/// - a u64 pointer to the JSON module graph embedded in the binary
 /// - a u64 pointer to JSON metadata (serialized flags) embedded in the binary
-/// These are dereferenced, and the bundle is executed under the configuration
-/// specified by the metadata. If no magic trailer is present, this function
-/// exits with `Ok(None)`.
+/// Files included with `--include` and the V8 snapshot created with
+/// `--snapshot` are stored in front of the module graph, at the offsets listed
+/// in the metadata.
+/// These are dereferenced, and the main module is executed under the
+/// configuration specified by the metadata. If no magic trailer is present,
+/// this function exits with `Ok(None)`.
 pub fn extract_standalone(
   args: Vec<String>,
-) -> Result<Option<(Metadata, String)>, AnyError> {
+) -> Result<Option<(Metadata, EmbeddedModules)>, AnyError> {
   let current_exe_path = current_exe()?;
 
   let mut current_exe = File::open(current_exe_path)?;
@@ -77,15 +147,17 @@ pub fn extract_standalone(
   let metadata_len = trailer_pos - metadata_pos;
   current_exe.seek(SeekFrom::Start(bundle_pos))?;
 
-  let bundle = read_string_slice(&mut current_exe, bundle_pos, bundle_len)
-    .context("Failed to read source bundle from the current executable")?;
+  let modules = read_string_slice(&mut current_exe, bundle_pos, bundle_len)
+    .context("Failed to read modules from the current executable")?;
   let metadata =
     read_string_slice(&mut current_exe, metadata_pos, metadata_len)
       .context("Failed to read metadata from the current executable")?;
 
+  let modules: EmbeddedModules = serde_json::from_str(&modules)
+    .context("Failed to parse modules embedded in the current executable")?;
   let mut metadata: Metadata = serde_json::from_str(&metadata).unwrap();
   metadata.argv.append(&mut args[1..].to_vec());
-  Ok(Some((metadata, bundle)))
+  Ok(Some((metadata, modules)))
 }
 
 fn u64_from_bytes(arr: &[u8]) -> Result<u64, AnyError> {
@@ -95,6 +167,17 @@ fn u64_from_bytes(arr: &[u8]) -> Result<u64, AnyError> {
   Ok(u64::from_be_bytes(*fixed_arr))
 }
 
+fn read_slice(
+  file: &mut File,
+  pos: u64,
+  len: u64,
+) -> Result<Vec<u8>, AnyError> {
+  let mut buf = Vec::new();
+  file.seek(SeekFrom::Start(pos))?;
+  file.take(len).read_to_end(&mut buf)?;
+  Ok(buf)
+}
+
 fn read_string_slice(
   file: &mut File,
   pos: u64,
@@ -107,24 +190,128 @@ fn read_string_slice(
   Ok(string)
 }
 
-const SPECIFIER: &str = "file://$deno$/bundle.js";
+impl SourceMapGetter for EmbeddedModules {
+  fn get_source_map(&self, file_name: &str) -> Option<Vec<u8>> {
+    let specifier = Url::parse(file_name).ok()?;
+    let (_, module) = self.get(&specifier)?;
+    module.map.as_ref().map(|map| map.as_bytes().to_vec())
+  }
+
+  fn get_source_line(
+    &self,
+    file_name: &str,
+    line_number: usize,
+  ) -> Option<String> {
+    let specifier = Url::parse(file_name).ok()?;
+    let (_, module) = self.get(&specifier)?;
+    // The code of transpiled modules doesn't match their source, whose lines
+    // are then taken from the source map.
+    if module.map.is_some() {
+      return None;
+    }
+    module.code.split('\n').nth(line_number).map(String::from)
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApplySourceMapArgs {
+  file_name: String,
+  line_number: i32,
+  column_number: i32,
+}
+
+/// The standalone counterpart of `op_apply_source_map` in `cli/ops/errors.rs`,
+/// which maps stack frames using the embedded source maps.
+fn op_apply_source_map(
+  state: &mut OpState,
+  args: ApplySourceMapArgs,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  let mut mappings_map: CachedMaps = HashMap::new();
+  let modules = state.borrow::<Arc<EmbeddedModules>>().clone();
 
-struct EmbeddedModuleLoader(String);
+  let (orig_file_name, orig_line_number, orig_column_number, _) =
+    get_orig_position(
+      args.file_name,
+      args.line_number.into(),
+      args.column_number.into(),
+      &mut mappings_map,
+      modules,
+    );
+
+  Ok(json!({
+    "fileName": orig_file_name,
+    "lineNumber": orig_line_number as u32,
+    "columnNumber": orig_column_number as u32,
+  }))
+}
+
+fn create_js_error_create_fn(
+  modules: Arc<EmbeddedModules>,
+) -> Rc<JsErrorCreateFn> {
+  Rc::new(move |core_js_error| {
+    let source_mapped_error = apply_source_map(&core_js_error, modules.clone());
+    PrettyJsError::create(source_mapped_error)
+  })
+}
+
+/// Set up the state and ops the workers of a standalone binary need on top of
+/// those of the runtime.
+fn init_standalone_runtime(
+  js_runtime: &mut JsRuntime,
+  modules: Arc<EmbeddedModules>,
+  embedded_files: EmbeddedFiles,
+) {
+  {
+    let op_state = js_runtime.op_state();
+    let mut op_state = op_state.borrow_mut();
+    op_state.put(modules);
+    op_state.put(embedded_files);
+  }
+  deno_runtime::ops::reg_json_sync(
+    js_runtime,
+    "op_apply_source_map",
+    op_apply_source_map,
+  );
+}
+
+struct EmbeddedModuleLoader {
+  modules: Arc<EmbeddedModules>,
+  /// The modules evaluated into the snapshot the runtime was restored from.
+  snapshot_modules: HashSet<String>,
+  /// The modules loaded so far, which are recorded while creating a snapshot.
+  loaded: RefCell<Vec<String>>,
+}
+
+impl EmbeddedModuleLoader {
+  fn new(modules: Arc<EmbeddedModules>) -> Self {
+    Self {
+      modules,
+      snapshot_modules: HashSet::new(),
+      loaded: RefCell::new(Vec::new()),
+    }
+  }
+}
 
 impl ModuleLoader for EmbeddedModuleLoader {
   fn resolve(
     &self,
     _op_state: Rc<RefCell<OpState>>,
     specifier: &str,
-    _referrer: &str,
+    referrer: &str,
     _is_main: bool,
   ) -> Result<ModuleSpecifier, AnyError> {
-    if specifier != SPECIFIER {
-      return Err(type_error(
-        "Self-contained binaries don't support module loading",
-      ));
+    // Prefer the resolution done when the binary was compiled, as it accounts
+    // for import maps.
+    if let Ok(referrer) = Url::parse(referrer) {
+      if let Some((_, module)) = self.modules.get(&referrer) {
+        if let Some(resolved) = module.dependencies.get(specifier) {
+          return Ok(resolved.clone());
+        }
+      }
     }
-    Ok(resolve_url(specifier)?)
+    Ok(resolve_import(specifier, referrer)?)
   }
 
   fn load(
@@ -135,50 +322,134 @@ impl ModuleLoader for EmbeddedModuleLoader {
     _is_dynamic: bool,
   ) -> Pin<Box<deno_core::ModuleSourceFuture>> {
     let module_specifier = module_specifier.clone();
-    let code = self.0.to_string();
-    async move {
-      if module_specifier.to_string() != SPECIFIER {
-        return Err(type_error(
-          "Self-contained binaries don't support module loading",
-        ));
+    let result = match self.modules.get(&module_specifier) {
+      Some((found, _)) if self.snapshot_modules.contains(found) => {
+        Err(type_error(format!(
+          "Module is part of the startup snapshot and can't be loaded again: {}",
+          module_specifier
+        )))
       }
-      Ok(deno_core::ModuleSource {
-        code,
-        module_url_specified: module_specifier.to_string(),
-        module_url_found: module_specifier.to_string(),
-      })
-    }
-    .boxed_local()
+      Some((found, module)) => {
+        self.loaded.borrow_mut().push(found.to_string());
+        Ok(deno_core::ModuleSource {
+          code: module.code.clone(),
+          module_url_specified: module_specifier.to_string(),
+          module_url_found: found.to_string(),
+        })
+      }
+      None => Err(type_error(format!(
+        "Module not found in the self-contained binary: {}",
+        module_specifier
+      ))),
+    };
+    async move { result }.boxed_local()
   }
 }
 
-pub async fn run(
-  source_code: String,
-  metadata: Metadata,
-) -> Result<(), AnyError> {
-  let main_module = resolve_url(SPECIFIER)?;
-  let permissions = Permissions::from_options(&metadata.permissions);
-  let module_loader = Rc::new(EmbeddedModuleLoader(source_code));
-  let create_web_worker_cb = Arc::new(|_| {
-    todo!("Worker are currently not supported in standalone binaries");
-  });
+/// The options shared by the main worker and web workers of a standalone
+/// binary.
+struct SharedOptions {
+  argv: Vec<String>,
+  debug_flag: bool,
+  unstable: bool,
+  ca_data: Option<Vec<u8>>,
+  ca_stores: Option<Vec<String>>,
+  proxy: Option<Proxy>,
+  seed: Option<u64>,
+  embedded_files: EmbeddedFiles,
+}
 
-  // Keep in sync with `main.rs`.
-  v8_set_flags(
-    once("UNUSED_BUT_NECESSARY_ARG0".to_owned())
-      .chain(metadata.v8_flags.iter().cloned())
-      .collect::<Vec<_>>(),
-  );
+fn create_web_worker_callback(
+  modules: Arc<EmbeddedModules>,
+  shared: Arc<SharedOptions>,
+) -> Arc<CreateWebWorkerCb> {
+  Arc::new(move |args| {
+    let module_loader = Rc::new(EmbeddedModuleLoader::new(modules.clone()));
+    let create_web_worker_cb =
+      create_web_worker_callback(modules.clone(), shared.clone());
+
+    let options = WebWorkerOptions {
+      args: shared.argv.clone(),
+      apply_source_maps: true,
+      debug_flag: shared.debug_flag,
+      unstable: shared.unstable,
+      ca_data: shared.ca_data.clone(),
+      ca_stores: shared.ca_stores.clone(),
+      proxy: shared.proxy.clone(),
+      user_agent: version::get_user_agent(),
+      seed: shared.seed,
+      module_loader,
+      create_web_worker_cb,
+      js_error_create_fn: Some(create_js_error_create_fn(modules.clone())),
+      use_deno_namespace: args.use_deno_namespace,
+      attach_inspector: false,
+      maybe_inspector_server: None,
+      runtime_version: version::deno(),
+      ts_version: version::TYPESCRIPT.to_string(),
+      no_color: !colors::use_color(),
+      get_error_class_fn: Some(&get_error_class_name),
+    };
+
+    let mut worker = WebWorker::from_options(
+      args.name,
+      args.permissions,
+      args.main_module,
+      args.worker_id,
+      &options,
+    );
+    init_standalone_runtime(
+      &mut worker.js_runtime,
+      modules.clone(),
+      shared.embedded_files.clone(),
+    );
+    worker.bootstrap(&options);
+    worker
+  })
+}
 
-  let options = WorkerOptions {
-    apply_source_maps: false,
-    args: metadata.argv,
-    debug_flag: metadata.log_level.map_or(false, |l| l == log::Level::Debug),
+impl SharedOptions {
+  fn new(
+    metadata: &Metadata,
+    embedded_files: EmbeddedFiles,
+  ) -> Result<Self, AnyError> {
+    let proxy = match &metadata.proxy {
+      Some(proxy) => Some(Proxy::from_url(proxy)?),
+      None => None,
+    };
+    Ok(Self {
+      argv: metadata.argv.clone(),
+      debug_flag: metadata.log_level.map_or(false, |l| l == log::Level::Debug),
+      unstable: metadata.unstable,
+      ca_data: metadata.ca_data.clone(),
+      ca_stores: metadata.ca_stores.clone(),
+      proxy,
+      seed: metadata.seed,
+      embedded_files,
+    })
+  }
+}
+
+fn create_main_worker_options(
+  modules: Arc<EmbeddedModules>,
+  module_loader: Rc<EmbeddedModuleLoader>,
+  metadata: &Metadata,
+  shared: Arc<SharedOptions>,
+) -> WorkerOptions {
+  let js_error_create_fn = create_js_error_create_fn(modules.clone());
+  let create_web_worker_cb =
+    create_web_worker_callback(modules, shared.clone());
+
+  WorkerOptions {
+    apply_source_maps: true,
+    args: metadata.argv.clone(),
+    debug_flag: shared.debug_flag,
     user_agent: version::get_user_agent(),
     unstable: metadata.unstable,
-    ca_data: metadata.ca_data,
+    ca_data: metadata.ca_data.clone(),
+    ca_stores: shared.ca_stores.clone(),
+    proxy: shared.proxy.clone(),
     seed: metadata.seed,
-    js_error_create_fn: None,
+    js_error_create_fn: Some(js_error_create_fn),
     create_web_worker_cb,
     attach_inspector: false,
     maybe_inspector_server: None,
@@ -188,12 +459,114 @@ pub async fn run(
     ts_version: version::TYPESCRIPT.to_string(),
     no_color: !colors::use_color(),
     get_error_class_fn: Some(&get_error_class_name),
-    location: metadata.location,
-  };
+    location: metadata.location.clone(),
+  }
+}
+
+/// Evaluate the main module of a standalone binary and take a V8 snapshot of
+/// the resulting heap, which the binary then starts from instead. The
+/// snapshot is only valid for the same build of Deno it was created by. The
+/// modules evaluated into the snapshot are recorded in the metadata.
+// allow(dead_code) because denort does not use this.
+#[allow(dead_code)]
+pub async fn create_snapshot(
+  modules: EmbeddedModules,
+  metadata: &mut Metadata,
+) -> Result<Vec<u8>, AnyError> {
+  let main_module = metadata.main_module.clone();
+  let permissions = Permissions::from_options(&metadata.permissions);
+  let modules = Arc::new(modules);
+  let shared =
+    Arc::new(SharedOptions::new(metadata, EmbeddedFiles::default())?);
+  let module_loader = Rc::new(EmbeddedModuleLoader::new(modules.clone()));
+  let options = create_main_worker_options(
+    modules.clone(),
+    module_loader.clone(),
+    metadata,
+    shared.clone(),
+  );
+
   let mut worker =
-    MainWorker::from_options(main_module.clone(), permissions, &options);
+    MainWorker::for_snapshot(main_module.clone(), permissions, &options);
+  init_standalone_runtime(
+    &mut worker.js_runtime,
+    modules,
+    shared.embedded_files.clone(),
+  );
   worker.bootstrap(&options);
   worker.execute_module(&main_module).await?;
+  let snapshot = worker.js_runtime.snapshot();
+  metadata.snapshot_modules = module_loader.loaded.borrow().clone();
+  Ok(snapshot.to_vec())
+}
+
+pub async fn run(
+  modules: EmbeddedModules,
+  metadata: Metadata,
+) -> Result<(), AnyError> {
+  let main_module = metadata.main_module.clone();
+  let permissions = Permissions::from_options(&metadata.permissions);
+  let current_exe_path = current_exe()?;
+  let maybe_snapshot = match metadata.snapshot {
+    Some((pos, len)) => {
+      let mut current_exe = File::open(&current_exe_path)?;
+      Some(
+        read_slice(&mut current_exe, pos, len)
+          .context("Failed to read snapshot from the current executable")?,
+      )
+    }
+    None => None,
+  };
+  let shared = Arc::new(SharedOptions::new(
+    &metadata,
+    EmbeddedFiles {
+      source: current_exe_path,
+      files: metadata.files.clone(),
+    },
+  )?);
+
+  // Keep in sync with `main.rs`.
+  v8_set_flags(
+    once("UNUSED_BUT_NECESSARY_ARG0".to_owned())
+      .chain(metadata.v8_flags.iter().cloned())
+      .collect::<Vec<_>>(),
+  );
+
+  let modules = Arc::new(modules);
+  let mut module_loader = EmbeddedModuleLoader::new(modules.clone());
+  if maybe_snapshot.is_some() {
+    module_loader.snapshot_modules =
+      metadata.snapshot_modules.iter().cloned().collect();
+  }
+  let options = create_main_worker_options(
+    modules.clone(),
+    Rc::new(module_loader),
+    &metadata,
+    shared.clone(),
+  );
+  let mut worker = match maybe_snapshot {
+    Some(snapshot) => MainWorker::from_snapshot(
+      main_module.clone(),
+      permissions,
+      &options,
+      Snapshot::Boxed(snapshot.into_boxed_slice()),
+    ),
+    None => {
+      MainWorker::from_options(main_module.clone(), permissions, &options)
+    }
+  };
+  init_standalone_runtime(
+    &mut worker.js_runtime,
+    modules,
+    shared.embedded_files.clone(),
+  );
+  if metadata.snapshot.is_some() {
+    // The main module was already evaluated when the snapshot was taken.
+    worker.restore(&options);
+  } else {
+    worker.bootstrap(&options);
+    worker.execute_module(&main_module).await?;
+  }
   worker.execute("window.dispatchEvent(new Event('load'))")?;
   worker.run_event_loop().await?;
   worker.execute("window.dispatchEvent(new Event('unload'))")?;
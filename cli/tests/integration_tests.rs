@@ -1226,6 +1226,95 @@ mod integration {
       .ends_with("hello, foo"));
   }
 
+  #[test]
+  fn installer_test_lock_run() {
+    let _g = util::http_server();
+    let temp_dir = TempDir::new().expect("tempdir fail");
+    let deno_dir = TempDir::new().expect("tempdir fail");
+    // The modules are written to a directory served by the test server, so a
+    // dependency can be changed after the installation.
+    let served_dir = tempfile::Builder::new()
+      .prefix("install_lock")
+      .tempdir_in(util::tests_path())
+      .expect("tempdir fail");
+    std::fs::write(
+      served_dir.path().join("main.ts"),
+      "import { message } from \"./dep.ts\";\nconsole.log(message);\n",
+    )
+    .unwrap();
+    std::fs::write(
+      served_dir.path().join("dep.ts"),
+      "export const message = \"hello\";\n",
+    )
+    .unwrap();
+    let module_url = format!(
+      "http://localhost:4545/cli/tests/{}/main.ts",
+      served_dir.path().file_name().unwrap().to_str().unwrap()
+    );
+
+    let status = util::deno_cmd()
+      .current_dir(util::root_path())
+      .env("DENO_DIR", deno_dir.path())
+      .arg("install")
+      .arg("--lock")
+      .arg(temp_dir.path().join("lock.json"))
+      .arg("--lock-write")
+      .arg("--name")
+      .arg("lock_test")
+      .arg("--root")
+      .arg(temp_dir.path())
+      .arg(&module_url)
+      .spawn()
+      .unwrap()
+      .wait()
+      .unwrap();
+    assert!(status.success());
+    let mut file_path = temp_dir.path().join("bin").join("lock_test");
+    if cfg!(windows) {
+      file_path = file_path.with_extension("cmd");
+    }
+
+    let output = Command::new(&file_path)
+      .current_dir(temp_dir.path())
+      .env("PATH", util::target_dir())
+      .env("DENO_DIR", deno_dir.path())
+      .output()
+      .expect("failed to spawn script");
+    assert!(output.status.success());
+    assert_eq!(std::str::from_utf8(&output.stdout).unwrap().trim(), "hello");
+
+    // Reloading the changed dependency into the cache must not change what
+    // the executable runs.
+    std::fs::write(
+      served_dir.path().join("dep.ts"),
+      "export const message = \"goodbye\";\n",
+    )
+    .unwrap();
+    let status = util::deno_cmd()
+      .current_dir(util::root_path())
+      .env("DENO_DIR", deno_dir.path())
+      .arg("cache")
+      .arg("--reload")
+      .arg(&module_url)
+      .spawn()
+      .unwrap()
+      .wait()
+      .unwrap();
+    assert!(status.success());
+
+    let output = Command::new(&file_path)
+      .current_dir(temp_dir.path())
+      .env("PATH", util::target_dir())
+      .env("DENO_DIR", deno_dir.path())
+      .output()
+      .expect("failed to spawn script");
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(std::str::from_utf8(&output.stderr)
+      .unwrap()
+      .contains("does not match the expected hash in the lock file"));
+  }
+
   #[test]
   fn ts_dependency_recompilation() {
     let t = TempDir::new().expect("tempdir fail");
@@ -2065,6 +2154,202 @@ mod integration {
       assert!(out.contains("hello!\n"));
     }
 
+    #[test]
+    fn import_declarations() {
+      let (out, _) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec![
+          "import { printHello } from './subdir/mod2.ts';",
+          "printHello()",
+        ]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("undefined\nHello\nundefined\n"));
+    }
+
+    #[test]
+    fn import_declarations_import_map() {
+      let (out, _) = util::run_and_collect_output(
+        true,
+        "repl --import-map=import_maps/import_map.json",
+        Some(vec!["import 'moment';"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.contains("Hello from remapped moment!\n"));
+    }
+
+    #[test]
+    fn typescript() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec![
+          "const a: number = 1;",
+          "a",
+          "interface Foo { bar: string }",
+          "enum Color { Red, Green }",
+          "Color.Green",
+          "function baz(foo: Foo): string { return foo.bar; }",
+          "baz({ bar: 'qux' } as Foo)",
+        ]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with(
+        "undefined\n1\nundefined\nundefined\n1\nundefined\n\"qux\"\n"
+      ));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn help_command() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec![".help"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.contains(".load    Evaluate a file in the REPL context\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn save_and_load_commands() {
+      let t = TempDir::new().expect("tempdir fail");
+      let session_file = t.path().join("session.ts");
+      let save_command = format!(".save {}", session_file.display());
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec!["const a: number = 42;", &save_command]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with(&format!(
+        "undefined\nSession saved to: {}\n",
+        session_file.display()
+      )));
+      assert!(err.is_empty());
+      assert_eq!(
+        std::fs::read_to_string(&session_file).unwrap(),
+        "const a: number = 42;\n"
+      );
+
+      let load_command = format!(".load {}", session_file.display());
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec![&load_command, "a"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("undefined\n42\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn editor_command() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec![".editor", "const a = 1;", "a + 1"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with(
+        "// Entering editor mode (ctrl+d to finish, ctrl+c to cancel)\n2\n"
+      ));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn clear_command() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec!["let a = 1;", ".clear", "typeof a"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("undefined\nClearing context...\n\"undefined\"\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn clear_command_resets_saved_inputs() {
+      let t = TempDir::new().expect("tempdir fail");
+      let session_file = t.path().join("session.ts");
+      let save_command = format!(".save {}", session_file.display());
+      let (_, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec!["let a = 1;", ".clear", "let b = 2;", &save_command]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(err.is_empty());
+      assert_eq!(
+        std::fs::read_to_string(&session_file).unwrap(),
+        "let b = 2;\n"
+      );
+    }
+
+    #[test]
+    fn leading_dot_expression() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec![".5 + 1"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("1.5\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn eval_file() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl --eval-file=repl_prelude.ts",
+        Some(vec!["greeting"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("exit using ctrl+d or close()\n\"Hello World\"\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn eval_file_module() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl --eval-file=repl_module.ts",
+        Some(vec!["message", "typeof greeting"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("\"Hello World from a module\"\n\"undefined\"\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn load_command_module() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec![".load repl_module.ts", "message"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("undefined\n\"Hello World from a module\"\n"));
+      assert!(err.is_empty());
+    }
+
     #[test]
     fn eval_unterminated() {
       let (out, err) = util::run_and_collect_output(
@@ -5314,13 +5599,18 @@ console.log("finish");
       .unwrap();
     assert!(!output.status.success());
     assert_eq!(output.stdout, b"");
-    let expected_stderr = "error: Error: boom!\n    at boom (file://$deno$/bundle.js:2:11)\n    at foo (file://$deno$/bundle.js:5:5)\n    at file://$deno$/bundle.js:7:1\n";
+    // The positions point at the TypeScript source rather than the
+    // transpiled code embedded in the binary, and the module is named
+    // relative to the compile root rather than by its path on this machine.
+    let expected_frames = "    at boom (file:///$deno$/standalone_error.ts:2:9)\n    at foo (file:///$deno$/standalone_error.ts:6:3)\n    at file:///$deno$/standalone_error.ts:9:1";
     let stderr = String::from_utf8(output.stderr).unwrap();
-    assert_eq!(stderr, expected_stderr);
+    assert!(stderr.starts_with("error: Uncaught Error: boom!\n"));
+    assert!(stderr.contains(expected_frames));
+    assert!(!stderr.contains(util::root_path().to_str().unwrap()));
   }
 
   #[test]
-  fn standalone_no_module_load() {
+  fn standalone_dynamic_import() {
     let dir = TempDir::new().expect("tempdir fail");
     let exe = if cfg!(windows) {
       dir.path().join("hello.exe")
@@ -5340,6 +5630,148 @@ console.log("finish");
       .wait_with_output()
       .unwrap();
     assert!(output.status.success());
+    let output = Command::new(exe)
+      .stdout(std::process::Stdio::piped())
+      .stderr(std::process::Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"start\nHello World\n");
+  }
+
+  #[test]
+  fn standalone_worker() {
+    let dir = TempDir::new().expect("tempdir fail");
+    let exe = if cfg!(windows) {
+      dir.path().join("worker.exe")
+    } else {
+      dir.path().join("worker")
+    };
+    let output = util::deno_cmd()
+      .current_dir(util::root_path())
+      .arg("compile")
+      .arg("--unstable")
+      .arg("--entry")
+      .arg("./cli/tests/standalone_worker_child.js")
+      .arg("--output")
+      .arg(&exe)
+      .arg("./cli/tests/standalone_worker.ts")
+      .stdout(std::process::Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap();
+    assert!(output.status.success());
+    let output = Command::new(exe)
+      .current_dir(dir.path())
+      .stdout(std::process::Stdio::piped())
+      .stderr(std::process::Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"ping pong\n");
+  }
+
+  #[test]
+  fn standalone_include() {
+    let dir = TempDir::new().expect("tempdir fail");
+    let exe = if cfg!(windows) {
+      dir.path().join("include.exe")
+    } else {
+      dir.path().join("include")
+    };
+    let output = util::deno_cmd()
+      .current_dir(util::root_path())
+      .arg("compile")
+      .arg("--unstable")
+      .arg("--include")
+      .arg("./cli/tests/standalone_include/*.txt")
+      .arg("--output")
+      .arg(&exe)
+      .arg("./cli/tests/standalone_include.ts")
+      .stdout(std::process::Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap();
+    assert!(output.status.success());
+    let output = Command::new(exe)
+      .current_dir(dir.path())
+      .stdout(std::process::Stdio::piped())
+      .stderr(std::process::Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+      output.stdout,
+      b"Hello World\nWorld\n12\ntrue\nHello World\ntrue\ntrue\n"
+    );
+  }
+
+  #[test]
+  fn standalone_snapshot() {
+    let dir = TempDir::new().expect("tempdir fail");
+    let exe = if cfg!(windows) {
+      dir.path().join("snapshot.exe")
+    } else {
+      dir.path().join("snapshot")
+    };
+    let output = util::deno_cmd()
+      .current_dir(util::root_path())
+      .arg("compile")
+      .arg("--unstable")
+      .arg("--snapshot")
+      .arg("--output")
+      .arg(&exe)
+      .arg("./cli/tests/standalone_snapshot.ts")
+      .arg("a")
+      .stdout(std::process::Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"evaluated\n");
+    let output = Command::new(exe)
+      .arg("b")
+      .current_dir(dir.path())
+      .stdout(std::process::Stdio::piped())
+      .stderr(std::process::Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"Hello World a b\ntrue\n");
+  }
+
+  #[test]
+  fn standalone_missing_module() {
+    let dir = TempDir::new().expect("tempdir fail");
+    let exe = if cfg!(windows) {
+      dir.path().join("worker.exe")
+    } else {
+      dir.path().join("worker")
+    };
+    let output = util::deno_cmd()
+      .current_dir(util::root_path())
+      .arg("compile")
+      .arg("--unstable")
+      .arg("--output")
+      .arg(&exe)
+      .arg("./cli/tests/standalone_worker.ts")
+      .stdout(std::process::Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap();
+    assert!(output.status.success());
     let output = Command::new(exe)
       .stdout(std::process::Stdio::piped())
       .stderr(std::process::Stdio::piped())
@@ -5348,10 +5780,9 @@ console.log("finish");
       .wait_with_output()
       .unwrap();
     assert!(!output.status.success());
-    assert_eq!(output.stdout, b"start\n");
     let stderr_str = String::from_utf8(output.stderr).unwrap();
     assert!(util::strip_ansi_codes(&stderr_str)
-      .contains("Self-contained binaries don't support module loading"));
+      .contains("Module not found in the self-contained binary"));
   }
 
   #[test]
@@ -0,0 +1,3 @@
+import { greeting } from "./repl_module_dep.ts";
+
+export const message: string = `${greeting} from a module`;
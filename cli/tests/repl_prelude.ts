@@ -0,0 +1 @@
+const greeting: string = "Hello World";
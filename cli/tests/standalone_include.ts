@@ -0,0 +1,36 @@
+const greeting = await Deno.readTextFile(
+  new URL("./standalone_include/greeting.txt", import.meta.url),
+);
+console.log(greeting.trim());
+
+const file = Deno.openSync(
+  new URL("./standalone_include/greeting.txt", import.meta.url),
+);
+Deno.seekSync(file.rid, 6, Deno.SeekMode.Start);
+const buf = new Uint8Array(5);
+Deno.readSync(file.rid, buf);
+console.log(new TextDecoder().decode(buf));
+console.log(Deno.fstatSync(file.rid).size);
+try {
+  Deno.ftruncateSync(file.rid);
+} catch (e) {
+  console.log(e instanceof Deno.errors.NotSupported);
+}
+file.close();
+
+console.log(
+  Deno.readTextFileSync("/$deno$/standalone_include/greeting.txt").trim(),
+);
+
+// Relative paths refer to the real file system.
+try {
+  Deno.readTextFileSync("standalone_include/greeting.txt");
+} catch (e) {
+  console.log(e instanceof Deno.errors.PermissionDenied);
+}
+
+try {
+  await Deno.readTextFile(new URL("./standalone_include.ts", import.meta.url));
+} catch (e) {
+  console.log(e instanceof Deno.errors.PermissionDenied);
+}
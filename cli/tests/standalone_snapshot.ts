@@ -0,0 +1,14 @@
+// Evaluated once, when the executable is compiled.
+const greeting = ["Hello", "World"].join(" ");
+console.log("evaluated");
+
+addEventListener("load", async () => {
+  console.log(greeting, Deno.args.join(" "));
+  // Importing the main module again would evaluate it a second time, as the
+  // restored runtime doesn't know the modules of the snapshot.
+  try {
+    await import(import.meta.url);
+  } catch (e) {
+    console.log(e.message.includes("part of the startup snapshot"));
+  }
+});
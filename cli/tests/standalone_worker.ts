@@ -0,0 +1,9 @@
+const worker = new Worker(
+  new URL("./standalone_worker_child.js", import.meta.url).href,
+  { type: "module" },
+);
+worker.onmessage = (e: MessageEvent) => {
+  console.log(e.data);
+  worker.terminate();
+};
+worker.postMessage("ping");
@@ -1063,6 +1063,32 @@ MNf4EgWfK+tZMnuqfpfO9740KzfcVoMNo4QJD4yn5YxroUOO/Azi
   },
 );
 
+unitTest(function fetchCustomHttpClientInvalidProxy(): void {
+  assertThrows(
+    () => {
+      Deno.createHttpClient({ proxy: { url: "ftp://localhost:4545" } });
+    },
+    TypeError,
+    "Unsupported proxy scheme",
+  );
+});
+
+unitTest(
+  { perms: { net: true } },
+  async function fetchCustomHttpClientUnreachableProxy(): Promise<void> {
+    const client = Deno.createHttpClient({
+      proxy: {
+        url: "http://127.0.0.1:4599",
+        basicAuth: { username: "deno", password: "land" },
+      },
+    });
+    await assertThrowsAsync(async () => {
+      await fetch("http://example.com/", { client });
+    });
+    client.close();
+  },
+);
+
 unitTest(
   {
     perms: { net: true },
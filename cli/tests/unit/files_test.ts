@@ -647,3 +647,43 @@ unitTest({ perms: { read: true } }, async function seekMode(): Promise<void> {
   assertEquals(new TextDecoder().decode(buf), "H");
   file.close();
 });
+
+unitTest(
+  { perms: { read: true, write: true } },
+  function fileLockSyncSuccess(): void {
+    const filename = Deno.makeTempDirSync() + "/test_lock_sync.txt";
+    const file = Deno.openSync(filename, { write: true, create: true });
+    file.lockSync(true);
+    file.unlockSync();
+    Deno.flockSync(file.rid);
+    Deno.funlockSync(file.rid);
+    file.close();
+    Deno.removeSync(filename);
+  },
+);
+
+unitTest(
+  { perms: { read: true, write: true } },
+  async function fileLockWaitsForUnlock(): Promise<void> {
+    const filename = Deno.makeTempDirSync() + "/test_lock.txt";
+    const file1 = await Deno.open(filename, { write: true, create: true });
+    const file2 = await Deno.open(filename, { write: true });
+    await file1.lock(true);
+
+    let locked = false;
+    const lockPromise = Deno.flock(file2.rid, true).then(() => {
+      locked = true;
+    });
+    await new Promise((resolve) => setTimeout(resolve, 100));
+    assert(!locked);
+
+    await Deno.funlock(file1.rid);
+    await lockPromise;
+    assert(locked);
+    await file2.unlock();
+
+    file1.close();
+    file2.close();
+    Deno.removeSync(filename);
+  },
+);
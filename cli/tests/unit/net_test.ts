@@ -357,6 +357,50 @@ unitTest(
   },
 );
 
+unitTest(
+  { ignore: Deno.build.os !== "linux", perms: { read: true, write: true } },
+  async function netUnixPacketAbstractSendReceive(): Promise<void> {
+    const alicePath = `\0deno-test-alice-${Deno.pid}`;
+    const bobPath = `\0deno-test-bob-${Deno.pid}`;
+    const alice = Deno.listenDatagram({
+      path: alicePath,
+      transport: "unixpacket",
+    });
+    assert(alice.addr.transport === "unixpacket");
+    assertEquals(alice.addr.path, alicePath);
+
+    const bob = Deno.listenDatagram({
+      path: bobPath,
+      transport: "unixpacket",
+    });
+    assert(bob.addr.transport === "unixpacket");
+    assertEquals(bob.addr.path, bobPath);
+
+    const sent = new Uint8Array([1, 2, 3]);
+    const byteLength = await alice.send(sent, bob.addr);
+    assertEquals(byteLength, 3);
+
+    const [recvd, remote] = await bob.receive();
+    assert(remote.transport === "unixpacket");
+    assertEquals(remote.path, null);
+    assertEquals(recvd, sent);
+    alice.close();
+    bob.close();
+  },
+);
+
+unitTest(
+  { ignore: Deno.build.os !== "darwin", perms: { read: true, write: true } },
+  function netUnixPacketAbstractNotSupported(): void {
+    assertThrows(() => {
+      Deno.listenDatagram({
+        path: "\0deno-test-abstract",
+        transport: "unixpacket",
+      });
+    }, Deno.errors.NotSupported);
+  },
+);
+
 // TODO(piscisaureus): Enable after Tokio v0.3/v1.0 upgrade.
 unitTest(
   { ignore: true, perms: { read: true, write: true } },
@@ -599,3 +643,79 @@ unitTest(
     listener.close();
   },
 );
+
+unitTest(
+  { perms: { net: true } },
+  async function netTcpSetSocketOptions(): Promise<void> {
+    const addr = { hostname: "127.0.0.1", port: 3510 };
+    const listener = Deno.listen(addr);
+    const acceptPromise = listener.accept();
+    const conn = await Deno.connect(addr);
+
+    conn.setNoDelay();
+    conn.setNoDelay(false);
+    conn.setKeepAlive(30_000);
+    conn.setKeepAlive(null);
+    conn.setSendBufferSize(64 * 1024);
+    conn.setRecvBufferSize(64 * 1024);
+
+    await conn.write(new Uint8Array([1]));
+    const acceptedConn = await acceptPromise;
+    const buf = new Uint8Array(1);
+    assertEquals(await acceptedConn.read(buf), 1);
+
+    conn.close();
+    acceptedConn.close();
+    listener.close();
+  },
+);
+
+unitTest(
+  { ignore: Deno.build.os === "windows", perms: { net: true } },
+  function netTcpListenReusePort(): void {
+    const addr = { hostname: "127.0.0.1", port: 3511, reusePort: true };
+    const listener1 = Deno.listen(addr);
+    const listener2 = Deno.listen(addr);
+    assertEquals(
+      (listener1.addr as Deno.NetAddr).port,
+      (listener2.addr as Deno.NetAddr).port,
+    );
+    listener1.close();
+    listener2.close();
+  },
+);
+
+unitTest(
+  { perms: { net: true } },
+  function netUdpMulticastOptions(): void {
+    const socket = Deno.listenDatagram({
+      hostname: "0.0.0.0",
+      port: 3513,
+      transport: "udp",
+    });
+    socket.joinMulticastV4("224.0.0.251", "127.0.0.1");
+    socket.setMulticastLoopback(false);
+    socket.setMulticastTTL(4);
+    socket.setBroadcast();
+    socket.leaveMulticast("224.0.0.251", "127.0.0.1");
+    socket.close();
+  },
+);
+
+unitTest(
+  { perms: { net: true } },
+  function netUdpJoinMulticastInvalidAddress(): void {
+    const socket = Deno.listenDatagram({
+      hostname: "127.0.0.1",
+      port: 3514,
+      transport: "udp",
+    });
+    assertThrows(() => {
+      socket.joinMulticastV4("127.0.0.1");
+    }, TypeError);
+    assertThrows(() => {
+      socket.joinMulticastV6("ff02::fb", "127.0.0.1" as unknown as number);
+    }, TypeError);
+    socket.close();
+  },
+);
@@ -510,3 +510,151 @@ unitTest({ perms: { run: true, read: true } }, function killFailed(): void {
 
   p.close();
 });
+
+unitTest(
+  { perms: { run: true, read: true } },
+  async function commandOutput(): Promise<void> {
+    const command = new Deno.Command(Deno.execPath(), {
+      args: [
+        "eval",
+        "console.log('hello'); console.error('world'); Deno.exit(3)",
+      ],
+    });
+    const output = await command.output();
+    assertEquals(output.success, false);
+    assertEquals(output.code, 3);
+    assertEquals(new TextDecoder().decode(output.stdout), "hello\n");
+    assertEquals(new TextDecoder().decode(output.stderr), "world\n");
+  },
+);
+
+unitTest(
+  { perms: { run: true, read: true } },
+  function commandSpawnSync(): void {
+    const command = new Deno.Command(Deno.execPath(), {
+      args: ["eval", "console.log('hello')"],
+    });
+    const output = command.spawnSync();
+    assertEquals(output.success, true);
+    assertEquals(output.code, 0);
+    assertEquals(new TextDecoder().decode(output.stdout), "hello\n");
+    assertEquals(output.stderr.length, 0);
+  },
+);
+
+unitTest(
+  { perms: { run: true, read: true } },
+  async function commandSpawnStdin(): Promise<void> {
+    const child = new Deno.Command(Deno.execPath(), {
+      args: ["eval", "await Deno.copy(Deno.stdin, Deno.stdout)"],
+      stdin: "piped",
+      stdout: "piped",
+    }).spawn();
+    await child.stdin!.write(new TextEncoder().encode("hello"));
+    child.stdin!.close();
+    const output = await child.output();
+    assertEquals(output.success, true);
+    assertEquals(new TextDecoder().decode(output.stdout), "hello");
+  },
+);
+
+unitTest(
+  { ignore: Deno.build.os === "windows", perms: { run: true } },
+  function commandClearEnv(): void {
+    const output = new Deno.Command("env", {
+      clearEnv: true,
+      env: { FOO: "bar" },
+    }).spawnSync();
+    assertEquals(output.success, true);
+    assertEquals(new TextDecoder().decode(output.stdout), "FOO=bar\n");
+  },
+);
+
+unitTest(
+  { perms: { run: true, read: true } },
+  async function commandAbortSignal(): Promise<void> {
+    const controller = new AbortController();
+    const command = new Deno.Command(Deno.execPath(), {
+      args: ["eval", "setTimeout(() => {}, 10000)"],
+      signal: controller.signal,
+    });
+    const outputPromise = command.output();
+    setTimeout(() => controller.abort(), 100);
+    const output = await outputPromise;
+    assertEquals(output.success, false);
+    if (Deno.build.os !== "windows") {
+      assertEquals(output.signal, Deno.Signal.SIGKILL);
+    }
+
+    assertThrows(() => {
+      command.spawn();
+    }, DOMException);
+  },
+);
+
+unitTest({ perms: { read: true } }, function commandPermissions(): void {
+  assertThrows(() => {
+    new Deno.Command(Deno.execPath(), { args: ["eval", ""] }).spawnSync();
+  }, Deno.errors.PermissionDenied);
+});
+
+unitTest(
+  { ignore: Deno.build.os === "windows", perms: { run: true, read: true } },
+  async function runPtyIsatty(): Promise<void> {
+    const p = Deno.runPty({
+      cmd: [
+        Deno.execPath(),
+        "eval",
+        "--unstable",
+        "const { columns, rows } = Deno.consoleSize(Deno.stdout.rid);" +
+        "console.log(Deno.isatty(Deno.stdin.rid), columns, rows)",
+      ],
+      cols: 100,
+      rows: 30,
+    });
+    assert(Deno.isatty(p.pty.rid));
+    const output = new TextDecoder().decode(await Deno.readAll(p.pty));
+    assertEquals(output, "true 100 30\r\n");
+    const status = await p.status();
+    assertEquals(status.success, true);
+    p.close();
+  },
+);
+
+unitTest(
+  { ignore: Deno.build.os === "windows", perms: { run: true, read: true } },
+  async function runPtyResize(): Promise<void> {
+    const p = Deno.runPty({
+      cmd: [Deno.execPath(), "eval", "setTimeout(() => {}, 10000)"],
+    });
+    assertEquals(Deno.consoleSize(p.pty.rid), { columns: 80, rows: 24 });
+    p.resize(132, 43);
+    assertEquals(Deno.consoleSize(p.pty.rid), { columns: 132, rows: 43 });
+    p.kill(Deno.Signal.SIGKILL);
+    await p.status();
+    p.close();
+  },
+);
+
+unitTest(
+  { ignore: Deno.build.os === "windows", perms: { run: true, read: true } },
+  async function runPtyRaw(): Promise<void> {
+    const p = Deno.runPty({
+      cmd: [
+        Deno.execPath(),
+        "eval",
+        "const buf = new Uint8Array(1);" +
+        "await Deno.stdin.read(buf);" +
+        "console.log(buf[0])",
+      ],
+      raw: true,
+    });
+    // In raw mode the input is neither echoed nor buffered until a newline.
+    await p.pty.write(new TextEncoder().encode("a"));
+    const output = new TextDecoder().decode(await Deno.readAll(p.pty));
+    assertEquals(output, "97\r\n");
+    const status = await p.status();
+    assertEquals(status.success, true);
+    p.close();
+  },
+);
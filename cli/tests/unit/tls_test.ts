@@ -344,6 +344,274 @@ unitTest(
   },
 );
 
+unitTest(
+  { perms: { read: true, net: true } },
+  async function dialAndListenTLSClientCert(): Promise<void> {
+    const listener = Deno.listenTls({
+      hostname: "localhost",
+      port: 3505,
+      certFile: "cli/tests/tls/localhost.crt",
+      keyFile: "cli/tests/tls/localhost.key",
+      clientCa: Deno.readTextFileSync("cli/tests/tls/RootCA.pem"),
+    });
+
+    const acceptPromise = listener.accept();
+    const clientConn = await Deno.connectTls({
+      hostname: "localhost",
+      port: 3505,
+      certFile: "cli/tests/tls/RootCA.pem",
+      certChain: Deno.readTextFileSync("cli/tests/tls/localhost.crt"),
+      privateKey: Deno.readTextFileSync("cli/tests/tls/localhost.key"),
+    });
+    const serverConn = await acceptPromise;
+
+    assert(serverConn.peerCertificates !== null);
+    assertEquals(serverConn.peerCertificates.length, 1);
+    assert(
+      serverConn.peerCertificates[0].startsWith(
+        "-----BEGIN CERTIFICATE-----\n",
+      ),
+    );
+    assert(clientConn.peerCertificates !== null);
+    assertEquals(clientConn.peerCertificates, serverConn.peerCertificates);
+
+    await clientConn.write(new Uint8Array([1]));
+    const buf = new Uint8Array(1);
+    assertEquals(await serverConn.read(buf), 1);
+    assertEquals(buf[0], 1);
+
+    clientConn.close();
+    serverConn.close();
+    listener.close();
+  },
+);
+
+unitTest(
+  { perms: { read: true, net: true } },
+  async function listenTLSRequireClientCert(): Promise<void> {
+    const listener = Deno.listenTls({
+      hostname: "localhost",
+      port: 3506,
+      certFile: "cli/tests/tls/localhost.crt",
+      keyFile: "cli/tests/tls/localhost.key",
+      clientCa: Deno.readTextFileSync("cli/tests/tls/RootCA.pem"),
+    });
+
+    const acceptPromise = listener.accept();
+    // Depending on the TLS version, the client may finish its side of the
+    // handshake before the server rejects it.
+    const connectPromise = Deno.connectTls({
+      hostname: "localhost",
+      port: 3506,
+      certFile: "cli/tests/tls/RootCA.pem",
+    }).catch(() => null);
+
+    await assertThrowsAsync(async () => {
+      await acceptPromise;
+    });
+
+    (await connectPromise)?.close();
+    listener.close();
+  },
+);
+
+unitTest(
+  { perms: { read: true, net: true } },
+  async function dialAndListenTLSAlpn(): Promise<void> {
+    const listener = Deno.listenTls({
+      hostname: "localhost",
+      port: 3507,
+      certFile: "cli/tests/tls/localhost.crt",
+      keyFile: "cli/tests/tls/localhost.key",
+      alpnProtocols: ["h2", "http/1.1"],
+    });
+
+    const acceptPromise = listener.accept();
+    const clientConn = await Deno.connectTls({
+      hostname: "localhost",
+      port: 3507,
+      certFile: "cli/tests/tls/RootCA.pem",
+      alpnProtocols: ["h2"],
+    });
+    const serverConn = await acceptPromise;
+
+    assertEquals(clientConn.alpnProtocol, "h2");
+    assertEquals(serverConn.alpnProtocol, "h2");
+
+    clientConn.close();
+    serverConn.close();
+    listener.close();
+  },
+);
+
+unitTest(
+  { perms: { read: true, net: true } },
+  async function dialAndListenTLSSni(): Promise<void> {
+    const listener = Deno.listenTls({
+      hostname: "localhost",
+      port: 3508,
+      certFile: "cli/tests/tls/localhost.crt",
+      keyFile: "cli/tests/tls/localhost.key",
+      sniCertificates: {
+        localhost: {
+          certFile: "cli/tests/tls/localhost.crt",
+          keyFile: "cli/tests/tls/localhost.key",
+        },
+      },
+    });
+
+    const acceptPromise = listener.accept();
+    const clientConn = await Deno.connectTls({
+      hostname: "localhost",
+      port: 3508,
+      certFile: "cli/tests/tls/RootCA.pem",
+    });
+    const serverConn = await acceptPromise;
+
+    assert(clientConn.peerCertificates !== null);
+    assertEquals(clientConn.peerCertificates.length, 1);
+    assertEquals(serverConn.alpnProtocol, null);
+
+    clientConn.close();
+    serverConn.close();
+    listener.close();
+  },
+);
+
+unitTest(
+  { perms: { read: true, net: true } },
+  function listenTLSSniCertificateMismatch(): void {
+    assertThrows(() => {
+      Deno.listenTls({
+        hostname: "localhost",
+        port: 3509,
+        certFile: "cli/tests/tls/localhost.crt",
+        keyFile: "cli/tests/tls/localhost.key",
+        sniCertificates: {
+          "example.com": {
+            certFile: "cli/tests/tls/localhost.crt",
+            keyFile: "cli/tests/tls/localhost.key",
+          },
+        },
+      });
+    }, Deno.errors.InvalidData);
+  },
+);
+
+unitTest(
+  { perms: { read: true, net: true } },
+  async function startTlsServer(): Promise<void> {
+    const listener = Deno.listen({ hostname: "localhost", port: 3510 });
+    const acceptPromise = listener.accept().then((conn) =>
+      Deno.startTls(conn, {
+        certFile: "cli/tests/tls/localhost.crt",
+        keyFile: "cli/tests/tls/localhost.key",
+        server: true,
+      })
+    );
+    const clientConn = await Deno.startTls(
+      await Deno.connect({ hostname: "localhost", port: 3510 }),
+      { hostname: "localhost", certFile: "cli/tests/tls/RootCA.pem" },
+    );
+    const serverConn = await acceptPromise;
+
+    await clientConn.write(new Uint8Array([1]));
+    const buf = new Uint8Array(1);
+    assertEquals(await serverConn.read(buf), 1);
+    assertEquals(buf[0], 1);
+    assertEquals(serverConn.peerCertificates, null);
+
+    clientConn.close();
+    serverConn.close();
+    listener.close();
+  },
+);
+
+unitTest(
+  { perms: { read: true, net: true } },
+  async function startTlsServerWithoutKeyFile(): Promise<void> {
+    const listener = Deno.listen({ hostname: "localhost", port: 3511 });
+    const connectPromise = Deno.connect({ hostname: "localhost", port: 3511 });
+    const conn = await listener.accept();
+
+    await assertThrowsAsync(async () => {
+      await Deno.startTls(conn, {
+        certFile: "cli/tests/tls/localhost.crt",
+        server: true,
+      } as Deno.StartTlsServerOptions);
+    }, TypeError);
+
+    conn.close();
+    (await connectPromise).close();
+    listener.close();
+  },
+);
+
+unitTest(
+  { perms: { read: true, net: true } },
+  async function dialAndListenTLSPem(): Promise<void> {
+    const cert = Deno.readTextFileSync("cli/tests/tls/localhost.crt");
+    const key = Deno.readTextFileSync("cli/tests/tls/localhost.key");
+    const caCert = Deno.readTextFileSync("cli/tests/tls/RootCA.pem");
+    const [serverConn, clientConn] = await tlsPemPair(3512, cert, key, caCert);
+
+    await clientConn.write(new Uint8Array([1]));
+    const buf = new Uint8Array(1);
+    assertEquals(await serverConn.read(buf), 1);
+    assertEquals(buf[0], 1);
+
+    clientConn.close();
+    serverConn.close();
+  },
+);
+
+async function tlsPemPair(
+  port: number,
+  cert: string,
+  key: string,
+  caCert: string,
+): Promise<[Deno.Conn, Deno.Conn]> {
+  const listener = Deno.listenTls({ hostname: "localhost", port, cert, key });
+  const acceptPromise = listener.accept();
+  const connectPromise = Deno.connectTls({
+    hostname: "localhost",
+    port,
+    caCerts: [caCert],
+  });
+  const connections = await Promise.all([acceptPromise, connectPromise]);
+  listener.close();
+  return connections;
+}
+
+unitTest(
+  { perms: { net: true } },
+  function listenTLSPemNoReadPerm(): void {
+    // Without any file paths no read permission is required, so decoding the
+    // certificate fails instead.
+    assertThrows(() => {
+      Deno.listenTls({
+        hostname: "localhost",
+        port: 3514,
+        cert: "not a certificate",
+        key: "not a key",
+      });
+    }, Deno.errors.InvalidData);
+  },
+);
+
+unitTest(
+  { perms: { net: true } },
+  async function connectTLSInvalidCaCerts(): Promise<void> {
+    await assertThrowsAsync(async () => {
+      await Deno.connectTls({
+        hostname: "localhost",
+        port: 3515,
+        caCerts: ["not a certificate"],
+      });
+    }, Deno.errors.InvalidData);
+  },
+);
+
 unitTest(
   { perms: { read: true, net: true } },
   async function startTls(): Promise<void> {
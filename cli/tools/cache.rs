@@ -0,0 +1,364 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! Inspection and garbage collection of the contents of `$DENO_DIR`.
+
+use crate::colors;
+use crate::disk_cache::DiskCache;
+use crate::http_cache::HttpCache;
+use crate::http_cache::Metadata;
+use crate::http_util::HeadersMap;
+use crate::info::human_size;
+use deno_core::error::AnyError;
+use deno_core::url::Url;
+use deno_core::ModuleSpecifier;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+/// Headers stored in the module metadata which are shown by `deno cache
+/// --list`.
+const LISTED_HEADERS: &[&str] = &[
+  "content-type",
+  "etag",
+  "last-modified",
+  "cache-control",
+  "x-typescript-types",
+];
+
+/// The extensions of the files written to the `gen/` cache for every module.
+const GEN_EXTENSIONS: &[&str] = &["js", "js.map", "meta", "buildinfo"];
+
+/// A remote module stored in the `deps/` cache.
+#[derive(Debug)]
+pub struct CacheEntry {
+  pub url: Url,
+  pub path: PathBuf,
+  pub size: u64,
+  pub modified: Option<SystemTime>,
+  pub headers: HeadersMap,
+}
+
+impl fmt::Display for CacheEntry {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "{}", colors::bold(self.url.as_str()))?;
+    write!(f, "  size: {}", human_size(self.size as f64))?;
+    if let Some(age) = self.modified.and_then(|m| m.elapsed().ok()) {
+      write!(f, ", fetched: {}", human_age(age))?;
+    }
+    writeln!(f)?;
+    for name in LISTED_HEADERS {
+      if let Some(value) = self.headers.get(*name) {
+        writeln!(f, "  {}: {}", colors::gray(name), value)?;
+      }
+    }
+    Ok(())
+  }
+}
+
+/// The result of a garbage collection run.
+#[derive(Debug, Default, PartialEq)]
+pub struct GcStats {
+  pub removed_files: usize,
+  pub removed_bytes: u64,
+}
+
+impl fmt::Display for GcStats {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "Removed {} file{} ({})",
+      self.removed_files,
+      if self.removed_files == 1 { "" } else { "s" },
+      human_size(self.removed_bytes as f64)
+    )
+  }
+}
+
+fn human_age(age: Duration) -> String {
+  let secs = age.as_secs();
+  if secs < 60 {
+    "just now".to_string()
+  } else if secs < 60 * 60 {
+    format!("{}m ago", secs / 60)
+  } else if secs < 60 * 60 * 24 {
+    format!("{}h ago", secs / (60 * 60))
+  } else {
+    format!("{}d ago", secs / (60 * 60 * 24))
+  }
+}
+
+fn is_metadata_file(path: &Path) -> bool {
+  path
+    .file_name()
+    .and_then(|name| name.to_str())
+    .map(|name| name.ends_with(".metadata.json"))
+    .unwrap_or(false)
+}
+
+fn is_older_than(path: &Path, max_age: Option<Duration>) -> bool {
+  match max_age {
+    Some(max_age) => fs::metadata(path)
+      .and_then(|m| m.modified())
+      .ok()
+      .and_then(|modified| modified.elapsed().ok())
+      .map(|age| age >= max_age)
+      .unwrap_or(false),
+    None => false,
+  }
+}
+
+fn remove_file(path: &Path, stats: &mut GcStats) -> Result<(), AnyError> {
+  let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+  fs::remove_file(path)?;
+  stats.removed_files += 1;
+  stats.removed_bytes += size;
+  Ok(())
+}
+
+/// Remove every directory below `root` which has been left empty.
+fn remove_empty_dirs(root: &Path) {
+  for entry in WalkDir::new(root)
+    .min_depth(1)
+    .contents_first(true)
+    .into_iter()
+    .filter_map(|e| e.ok())
+    .filter(|e| e.file_type().is_dir())
+  {
+    // Only succeeds when the directory is empty.
+    let _ = fs::remove_dir(entry.path());
+  }
+}
+
+/// List every remote module stored in the `deps/` cache, sorted by URL.
+pub fn list(http_cache: &HttpCache) -> Result<Vec<CacheEntry>, AnyError> {
+  let mut entries = Vec::new();
+  if !http_cache.location.is_dir() {
+    return Ok(entries);
+  }
+  for entry in WalkDir::new(&http_cache.location)
+    .into_iter()
+    .filter_map(|e| e.ok())
+    .filter(|e| e.file_type().is_file() && !is_metadata_file(e.path()))
+  {
+    let path = entry.path().to_path_buf();
+    let metadata = match Metadata::read(&path) {
+      Ok(metadata) => metadata,
+      Err(_) => continue,
+    };
+    let url = match Url::parse(&metadata.url) {
+      Ok(url) => url,
+      Err(_) => continue,
+    };
+    let file_metadata = entry.metadata()?;
+    entries.push(CacheEntry {
+      url,
+      path,
+      size: file_metadata.len(),
+      modified: file_metadata.modified().ok(),
+      headers: metadata.headers,
+    });
+  }
+  entries.sort_by(|a, b| a.url.cmp(&b.url));
+  Ok(entries)
+}
+
+/// Remove entries from the `deps/` and `gen/` caches.
+///
+/// When `maybe_reachable` is provided, every entry which does not belong to
+/// one of those specifiers is removed. When `max_age` is provided, every entry
+/// which was written longer ago than `max_age` is removed as well.
+pub fn gc(
+  http_cache: &HttpCache,
+  gen_cache: &DiskCache,
+  maybe_reachable: Option<&HashSet<ModuleSpecifier>>,
+  max_age: Option<Duration>,
+) -> Result<GcStats, AnyError> {
+  let mut stats = GcStats::default();
+
+  if http_cache.location.is_dir() {
+    let keep: Option<HashSet<PathBuf>> = maybe_reachable.map(|reachable| {
+      reachable
+        .iter()
+        .filter_map(|s| http_cache.get_cache_filename(s))
+        .collect()
+    });
+    let files: Vec<PathBuf> = WalkDir::new(&http_cache.location)
+      .into_iter()
+      .filter_map(|e| e.ok())
+      .filter(|e| e.file_type().is_file() && !is_metadata_file(e.path()))
+      .map(|e| e.into_path())
+      .collect();
+    for path in files {
+      let unreachable = keep
+        .as_ref()
+        .map(|keep| !keep.contains(&path))
+        .unwrap_or(false);
+      if unreachable || is_older_than(&path, max_age) {
+        let metadata_path = Metadata::filename(&path);
+        remove_file(&path, &mut stats)?;
+        if metadata_path.is_file() {
+          remove_file(&metadata_path, &mut stats)?;
+        }
+      }
+    }
+    remove_empty_dirs(&http_cache.location);
+  }
+
+  if gen_cache.location.is_dir() {
+    let keep: Option<HashSet<PathBuf>> = maybe_reachable.map(|reachable| {
+      reachable
+        .iter()
+        .flat_map(move |s| {
+          GEN_EXTENSIONS
+            .iter()
+            .filter_map(move |ext| {
+              gen_cache.get_cache_filename_with_extension(s, ext)
+            })
+            .map(move |filename| gen_cache.location.join(filename))
+        })
+        .collect()
+    });
+    let files: Vec<PathBuf> = WalkDir::new(&gen_cache.location)
+      .min_depth(1)
+      .into_iter()
+      .filter_map(|e| e.ok())
+      .filter(|e| e.file_type().is_file())
+      .map(|e| e.into_path())
+      .collect();
+    for path in files {
+      // Only touch the per module emits, which are stored in a directory
+      // named after the scheme of their specifier.
+      let is_module_emit = path
+        .strip_prefix(&gen_cache.location)
+        .ok()
+        .and_then(|p| p.components().next())
+        .and_then(|c| c.as_os_str().to_str())
+        .map(|scheme| {
+          ["file", "http", "https", "data", "wasm"].contains(&scheme)
+        })
+        .unwrap_or(false);
+      if !is_module_emit {
+        continue;
+      }
+      let unreachable = keep
+        .as_ref()
+        .map(|keep| !keep.contains(&path))
+        .unwrap_or(false);
+      if unreachable || is_older_than(&path, max_age) {
+        remove_file(&path, &mut stats)?;
+      }
+    }
+    remove_empty_dirs(&gen_cache.location);
+  }
+
+  Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use deno_core::resolve_url;
+  use std::collections::HashMap;
+  use tempfile::TempDir;
+
+  fn setup() -> (TempDir, HttpCache, DiskCache) {
+    let temp_dir = TempDir::new().unwrap();
+    let http_cache = HttpCache::new(&temp_dir.path().join("deps"));
+    let gen_cache = DiskCache::new(&temp_dir.path().join("gen"));
+    (temp_dir, http_cache, gen_cache)
+  }
+
+  fn cache_module(
+    http_cache: &HttpCache,
+    gen_cache: &DiskCache,
+    specifier: &ModuleSpecifier,
+  ) {
+    let mut headers = HashMap::new();
+    headers.insert("etag".to_string(), "\"abc\"".to_string());
+    http_cache
+      .set(specifier, headers, b"export const a = 1;")
+      .unwrap();
+    let emit = gen_cache
+      .get_cache_filename_with_extension(specifier, "js")
+      .unwrap();
+    gen_cache.set(&emit, b"export const a = 1;").unwrap();
+  }
+
+  #[test]
+  fn test_list() {
+    let (_temp_dir, http_cache, gen_cache) = setup();
+    let a = resolve_url("https://deno.land/x/a.ts").unwrap();
+    let b = resolve_url("http://localhost:4545/b.ts").unwrap();
+    cache_module(&http_cache, &gen_cache, &a);
+    cache_module(&http_cache, &gen_cache, &b);
+
+    let entries = list(&http_cache).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].url, b);
+    assert_eq!(entries[1].url, a);
+    assert_eq!(entries[1].size, 19);
+    assert_eq!(entries[1].headers.get("etag").unwrap(), "\"abc\"");
+    assert!(entries[1].to_string().contains("etag"));
+  }
+
+  #[test]
+  fn test_gc_unreachable() {
+    let (_temp_dir, http_cache, gen_cache) = setup();
+    let a = resolve_url("https://deno.land/x/a.ts").unwrap();
+    let b = resolve_url("https://deno.land/x/b.ts").unwrap();
+    cache_module(&http_cache, &gen_cache, &a);
+    cache_module(&http_cache, &gen_cache, &b);
+
+    let mut reachable = HashSet::new();
+    reachable.insert(a.clone());
+    let stats = gc(&http_cache, &gen_cache, Some(&reachable), None).unwrap();
+    // The module, its metadata and its emit.
+    assert_eq!(stats.removed_files, 3);
+
+    let entries = list(&http_cache).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].url, a);
+    let emit_a = gen_cache
+      .get_cache_filename_with_extension(&a, "js")
+      .unwrap();
+    let emit_b = gen_cache
+      .get_cache_filename_with_extension(&b, "js")
+      .unwrap();
+    assert!(gen_cache.location.join(emit_a).is_file());
+    assert!(!gen_cache.location.join(emit_b).is_file());
+  }
+
+  #[test]
+  fn test_gc_max_age() {
+    let (_temp_dir, http_cache, gen_cache) = setup();
+    let a = resolve_url("https://deno.land/x/a.ts").unwrap();
+    cache_module(&http_cache, &gen_cache, &a);
+
+    let stats = gc(
+      &http_cache,
+      &gen_cache,
+      None,
+      Some(Duration::from_secs(3600)),
+    )
+    .unwrap();
+    assert_eq!(stats, GcStats::default());
+
+    let stats =
+      gc(&http_cache, &gen_cache, None, Some(Duration::from_secs(0))).unwrap();
+    assert_eq!(stats.removed_files, 3);
+    assert!(list(&http_cache).unwrap().is_empty());
+  }
+
+  #[test]
+  fn test_human_age() {
+    assert_eq!(human_age(Duration::from_secs(5)), "just now");
+    assert_eq!(human_age(Duration::from_secs(120)), "2m ago");
+    assert_eq!(human_age(Duration::from_secs(7200)), "2h ago");
+    assert_eq!(human_age(Duration::from_secs(172_800)), "2d ago");
+  }
+}
@@ -1,12 +1,18 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
+use crate::file_fetcher::FileFetcher;
 use crate::flags::Flags;
 use crate::fs_util::canonicalize_path;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
+use deno_core::serde_json;
 use deno_core::url::Url;
+use deno_runtime::permissions::Permissions;
 use log::Level;
 use regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::fs::File;
@@ -14,6 +20,7 @@ use std::io;
 use std::io::Write;
 #[cfg(not(windows))]
 use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
 use std::path::PathBuf;
 
 lazy_static! {
@@ -26,6 +33,66 @@ lazy_static! {
     ).case_insensitive(true).build().unwrap();
 }
 
+/// The name of the file in the installation root which records the installed
+/// scripts.
+const MANIFEST_FILE_NAME: &str = "install_manifest.json";
+
+/// A script installed with `deno install`, as recorded in the manifest.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InstalledScript {
+  /// The URL of the main module of the script.
+  module_url: String,
+  /// The flags which the executable passes to `deno run`.
+  flags: Vec<String>,
+  /// The arguments which the executable passes to the script.
+  args: Vec<String>,
+  /// The copy of the lockfile used by the executable.
+  lock: Option<PathBuf>,
+  /// The version of the script, when the module URL contains one.
+  version: Option<String>,
+}
+
+impl InstalledScript {
+  fn executable_args(&self) -> Vec<String> {
+    let mut executable_args = vec!["run".to_string()];
+    executable_args.extend_from_slice(&self.flags);
+    executable_args.push(self.module_url.clone());
+    executable_args.extend_from_slice(&self.args);
+    executable_args
+  }
+}
+
+/// The manifest of the scripts installed in an installation root, keyed by
+/// the name of their executable.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct Manifest {
+  scripts: BTreeMap<String, InstalledScript>,
+}
+
+impl Manifest {
+  fn read(root: &Path) -> Result<Self, AnyError> {
+    let path = root.join(MANIFEST_FILE_NAME);
+    if !path.exists() {
+      return Ok(Manifest::default());
+    }
+    let contents = fs::read_to_string(&path)?;
+    serde_json::from_str(&contents).map_err(|err| {
+      generic_error(format!(
+        "Invalid install manifest \"{}\": {}",
+        path.display(),
+        err
+      ))
+    })
+  }
+
+  fn write(&self, root: &Path) -> Result<(), AnyError> {
+    let contents = serde_json::to_string_pretty(self)?;
+    fs::write(root.join(MANIFEST_FILE_NAME), contents)?;
+    Ok(())
+  }
+}
+
 pub fn is_remote_url(module_url: &str) -> bool {
   let lower = module_url.to_lowercase();
   lower.starts_with("http://") || lower.starts_with("https://")
@@ -104,6 +171,23 @@ exec deno {} "$@"
   Ok(())
 }
 
+fn resolve_installer_root(root: Option<PathBuf>) -> Result<PathBuf, io::Error> {
+  if let Some(root) = root {
+    canonicalize_path(&root)
+  } else {
+    get_installer_root()
+  }
+}
+
+fn get_executable_path(installation_dir: &Path, name: &str) -> PathBuf {
+  let file_path = installation_dir.join(name);
+  if cfg!(windows) {
+    file_path.with_extension("cmd")
+  } else {
+    file_path
+  }
+}
+
 fn get_installer_root() -> Result<PathBuf, io::Error> {
   if let Ok(env_dir) = env::var("DENO_INSTALL_ROOT") {
     if !env_dir.is_empty() {
@@ -141,6 +225,39 @@ pub fn infer_name_from_url(url: &Url) -> Option<String> {
   Some(stem)
 }
 
+/// Splits a path segment like `std@0.90.0` into its name and version. A
+/// leading `@`, as in npm scopes, is part of the name.
+fn split_version(segment: &str) -> (&str, Option<&str>) {
+  match segment.get(1..).and_then(|rest| rest.find('@')) {
+    Some(index) => (&segment[..=index], Some(&segment[index + 2..])),
+    None => (segment, None),
+  }
+}
+
+/// Returns the version of a URL which contains one in a path segment, like
+/// `https://deno.land/std@0.90.0/http/file_server.ts`.
+pub fn infer_version_from_url(url: &Url) -> Option<String> {
+  url
+    .path_segments()?
+    .find_map(|segment| split_version(segment).1)
+    .filter(|version| !version.is_empty())
+    .map(String::from)
+}
+
+/// Removes the version from a URL, so it resolves to the latest version on
+/// registries like `deno.land/x`.
+fn remove_version_from_url(url: &Url) -> Url {
+  let mut url = url.clone();
+  let path = url
+    .path()
+    .split('/')
+    .map(|segment| split_version(segment).0)
+    .collect::<Vec<_>>()
+    .join("/");
+  url.set_path(&path);
+  url
+}
+
 pub fn install(
   flags: Flags,
   module_url: &str,
@@ -149,11 +266,7 @@ pub fn install(
   root: Option<PathBuf>,
   force: bool,
 ) -> Result<(), AnyError> {
-  let root = if let Some(root) = root {
-    canonicalize_path(&root)?
-  } else {
-    get_installer_root()?
-  };
+  let root = resolve_installer_root(root)?;
   let installation_dir = root.join("bin");
 
   // ensure directory exists
@@ -189,11 +302,7 @@ pub fn install(
   };
 
   validate_name(name.as_str())?;
-  let mut file_path = installation_dir.join(&name);
-
-  if cfg!(windows) {
-    file_path = file_path.with_extension("cmd");
-  }
+  let mut file_path = get_executable_path(&installation_dir, &name);
 
   if file_path.exists() && !force {
     return Err(generic_error(
@@ -203,8 +312,7 @@ pub fn install(
 
   let mut extra_files: Vec<(PathBuf, String)> = vec![];
 
-  let mut executable_args = vec!["run".to_string()];
-  executable_args.extend_from_slice(&flags.to_permission_args());
+  let mut executable_args = flags.to_permission_args();
   if let Some(url) = flags.location.as_ref() {
     executable_args.push("--location".to_string());
     executable_args.push(url.to_string());
@@ -213,6 +321,10 @@ pub fn install(
     executable_args.push("--cert".to_string());
     executable_args.push(ca_file)
   }
+  if let Some(proxy) = flags.proxy {
+    executable_args.push("--proxy".to_string());
+    executable_args.push(proxy)
+  }
   if let Some(log_level) = flags.log_level {
     if log_level == Level::Error {
       executable_args.push("--quiet".to_string());
@@ -241,12 +353,14 @@ pub fn install(
     executable_args.push("--no-remote".to_string());
   }
 
-  if flags.lock_write {
-    executable_args.push("--lock-write".to_string());
+  // With a lockfile, the executable only runs the modules which were fetched
+  // and verified at installation, instead of fetching them again.
+  if flags.cached_only || flags.lock.is_some() {
+    executable_args.push("--cached-only".to_string());
   }
 
-  if flags.cached_only {
-    executable_args.push("--cached-only".to_string());
+  if flags.revalidate {
+    executable_args.push("--revalidate".to_string());
   }
 
   if !flags.v8_flags.is_empty() {
@@ -282,22 +396,35 @@ pub fn install(
     extra_files.push((copy_path, fs::read_to_string(config_path)?));
   }
 
+  // The lockfile is snapshotted after the module graph was checked against it,
+  // or written with --lock-write, and is never written by the executable.
+  let mut lock = None;
   if let Some(lock_path) = flags.lock {
     let mut copy_path = file_path.clone();
     copy_path.set_extension("lock.json");
     executable_args.push("--lock".to_string());
     executable_args.push(copy_path.to_str().unwrap().to_string());
-    extra_files.push((copy_path, fs::read_to_string(lock_path)?));
+    extra_files.push((copy_path.clone(), fs::read_to_string(lock_path)?));
+    lock = Some(copy_path);
   }
 
-  executable_args.push(module_url.to_string());
-  executable_args.extend_from_slice(&args);
+  let script = InstalledScript {
+    module_url: module_url.to_string(),
+    flags: executable_args,
+    args,
+    lock,
+    version: infer_version_from_url(&module_url),
+  };
 
-  generate_executable_file(file_path.to_owned(), executable_args)?;
+  generate_executable_file(file_path.to_owned(), script.executable_args())?;
   for (path, contents) in extra_files {
     fs::write(path, contents)?;
   }
 
+  let mut manifest = Manifest::read(&root)?;
+  manifest.scripts.insert(name.clone(), script);
+  manifest.write(&root)?;
+
   println!("✅ Successfully installed {}", name);
   println!("{}", file_path.to_string_lossy());
   if cfg!(windows) {
@@ -318,6 +445,143 @@ pub fn install(
   Ok(())
 }
 
+/// Removes the executable of an installed script, along with the files which
+/// were copied for it, and its entry in the manifest.
+pub fn uninstall(name: String, root: Option<PathBuf>) -> Result<(), AnyError> {
+  validate_name(&name)?;
+  let root = resolve_installer_root(root)?;
+  let installation_dir = root.join("bin");
+
+  let mut manifest = Manifest::read(&root)?;
+  let mut removed = manifest.scripts.remove(&name).is_some();
+
+  let file_path = get_executable_path(&installation_dir, &name);
+  // On Windows, a shell script is installed next to the batch file.
+  let mut paths = vec![file_path.clone(), file_path.with_extension("")];
+  for extension in &["import_map.json", "tsconfig.json", "lock.json"] {
+    paths.push(file_path.with_extension(extension));
+  }
+  for path in paths {
+    if path.is_file() {
+      fs::remove_file(&path)?;
+      removed = true;
+    }
+  }
+
+  if !removed {
+    return Err(generic_error(format!("No installation found for {}", name)));
+  }
+
+  manifest.write(&root)?;
+  println!("✅ Successfully uninstalled {}", name);
+  Ok(())
+}
+
+/// Prints the scripts recorded in the manifest of the installation root.
+pub fn list(root: Option<PathBuf>) -> Result<(), AnyError> {
+  let root = resolve_installer_root(root)?;
+  let manifest = Manifest::read(&root)?;
+  if manifest.scripts.is_empty() {
+    println!("No scripts installed in {}", root.display());
+    return Ok(());
+  }
+
+  let width = manifest
+    .scripts
+    .keys()
+    .map(|name| name.len())
+    .max()
+    .unwrap();
+  for (name, script) in &manifest.scripts {
+    println!("{:<width$}  {}", name, script.module_url, width = width);
+  }
+  Ok(())
+}
+
+/// Resolves the URL of every installed remote script again without its
+/// version, and updates the executables of the scripts which were installed at
+/// an older version than the one it resolves to.
+///
+/// A script which fails to upgrade doesn't stop the others from upgrading, and
+/// the manifest is written with the scripts which were upgraded before the
+/// failures are reported.
+pub async fn upgrade(
+  file_fetcher: &FileFetcher,
+  root: Option<PathBuf>,
+) -> Result<(), AnyError> {
+  let root = resolve_installer_root(root)?;
+  let installation_dir = root.join("bin");
+  let mut manifest = Manifest::read(&root)?;
+
+  let mut failed = Vec::new();
+  for (name, script) in manifest.scripts.iter_mut() {
+    if !is_remote_url(&script.module_url) {
+      println!("{} is installed from a local module, skipping", name);
+      continue;
+    }
+    if script.lock.is_some() {
+      println!(
+        "{} is pinned by a lockfile, skipping (Install it again to upgrade)",
+        name
+      );
+      continue;
+    }
+
+    if let Err(err) =
+      upgrade_script(file_fetcher, &installation_dir, name, script).await
+    {
+      eprintln!("Failed to upgrade {}: {}", name, err);
+      failed.push(name.clone());
+    }
+  }
+
+  manifest.write(&root)?;
+  if !failed.is_empty() {
+    return Err(generic_error(format!(
+      "Failed to upgrade {}",
+      failed.join(", ")
+    )));
+  }
+  Ok(())
+}
+
+/// Upgrades a single remote script, only updating its manifest entry once its
+/// executable has been written.
+async fn upgrade_script(
+  file_fetcher: &FileFetcher,
+  installation_dir: &Path,
+  name: &str,
+  script: &mut InstalledScript,
+) -> Result<(), AnyError> {
+  let module_url = Url::parse(&script.module_url)?;
+  let latest_url = remove_version_from_url(&module_url);
+  let file = file_fetcher
+    .fetch(&latest_url, &Permissions::allow_all())
+    .await?;
+  let latest_version = infer_version_from_url(&file.specifier);
+  if script.version.is_none()
+    || latest_version.is_none()
+    || latest_version == script.version
+  {
+    println!("{} is up to date", name);
+    return Ok(());
+  }
+
+  println!(
+    "Upgrading {} from {} to {}",
+    name,
+    script.version.as_ref().unwrap(),
+    latest_version.as_ref().unwrap()
+  );
+  let mut upgraded = script.clone();
+  upgraded.module_url = file.specifier.to_string();
+  upgraded.version = latest_version;
+  let file_path = get_executable_path(installation_dir, name);
+  generate_executable_file(file_path, upgraded.executable_args())?;
+  *script = upgraded;
+  Ok(())
+}
+
 fn is_in_path(dir: &PathBuf) -> bool {
   if let Some(paths) = env::var_os("PATH") {
     for p in env::split_paths(&paths) {
@@ -771,6 +1035,171 @@ mod tests {
     assert!(file_content_2.contains("cat.ts"));
   }
 
+  #[test]
+  fn install_infer_version_from_url() {
+    assert_eq!(
+      infer_version_from_url(
+        &Url::parse("https://deno.land/std@0.90.0/http/file_server.ts")
+          .unwrap()
+      ),
+      Some("0.90.0".to_string())
+    );
+    assert_eq!(
+      infer_version_from_url(
+        &Url::parse("https://deno.land/x/abc@v1.2.3/cli.ts").unwrap()
+      ),
+      Some("v1.2.3".to_string())
+    );
+    assert_eq!(
+      infer_version_from_url(
+        &Url::parse("https://esm.sh/@scope/abc@1.0.0/cli.js").unwrap()
+      ),
+      Some("1.0.0".to_string())
+    );
+    assert_eq!(
+      infer_version_from_url(
+        &Url::parse("https://deno.land/std/http/file_server.ts").unwrap()
+      ),
+      None
+    );
+    assert_eq!(
+      infer_version_from_url(&Url::parse("file:///abc/main.ts").unwrap()),
+      None
+    );
+  }
+
+  #[test]
+  fn install_remove_version_from_url() {
+    assert_eq!(
+      remove_version_from_url(
+        &Url::parse("https://deno.land/x/abc@v1.2.3/cli.ts").unwrap()
+      )
+      .as_str(),
+      "https://deno.land/x/abc/cli.ts"
+    );
+    assert_eq!(
+      remove_version_from_url(
+        &Url::parse("https://esm.sh/@scope/abc@1.0.0/cli.js").unwrap()
+      )
+      .as_str(),
+      "https://esm.sh/@scope/abc/cli.js"
+    );
+  }
+
+  #[test]
+  fn install_manifest() {
+    let temp_dir = TempDir::new().expect("tempdir fail");
+
+    install(
+      Flags {
+        allow_net: Some(vec![]),
+        ..Flags::default()
+      },
+      "http://localhost:4545/cli/tests/std@0.1.0/echo_server.ts",
+      vec!["--foo".to_string()],
+      Some("echo_test".to_string()),
+      Some(temp_dir.path().to_path_buf()),
+      false,
+    )
+    .expect("Install failed");
+
+    let manifest = Manifest::read(temp_dir.path()).unwrap();
+    assert_eq!(
+      manifest.scripts.get("echo_test"),
+      Some(&InstalledScript {
+        module_url: "http://localhost:4545/cli/tests/std@0.1.0/echo_server.ts"
+          .to_string(),
+        flags: vec!["--allow-net".to_string()],
+        args: vec!["--foo".to_string()],
+        lock: None,
+        version: Some("0.1.0".to_string()),
+      })
+    );
+  }
+
+  #[test]
+  fn install_with_lock() {
+    let temp_dir = TempDir::new().expect("tempdir fail");
+    let bin_dir = temp_dir.path().join("bin");
+    let lock_file_path = temp_dir.path().join("lock.json");
+    let lock = r#"{
+  "http://localhost:4545/cli/tests/cat.ts": "abc"
+}"#;
+    fs::write(&lock_file_path, lock).unwrap();
+
+    install(
+      Flags {
+        lock: Some(lock_file_path),
+        lock_write: true,
+        ..Flags::default()
+      },
+      "http://localhost:4545/cli/tests/cat.ts",
+      vec![],
+      Some("echo_test".to_string()),
+      Some(temp_dir.path().to_path_buf()),
+      false,
+    )
+    .expect("Install failed");
+
+    let lock_copy_path = bin_dir.join("echo_test.lock.json");
+    assert_eq!(fs::read_to_string(&lock_copy_path).unwrap(), lock);
+
+    let file_path = get_executable_path(&bin_dir, "echo_test");
+    let content = fs::read_to_string(file_path).unwrap();
+    assert!(content.contains("--cached-only"));
+    assert!(content.contains("--lock"));
+    assert!(content.contains(&*lock_copy_path.to_string_lossy()));
+    assert!(!content.contains("--lock-write"));
+
+    let manifest = Manifest::read(temp_dir.path()).unwrap();
+    assert_eq!(
+      manifest.scripts.get("echo_test").unwrap().lock,
+      Some(lock_copy_path)
+    );
+  }
+
+  #[test]
+  fn uninstall_basic() {
+    let temp_dir = TempDir::new().expect("tempdir fail");
+    let bin_dir = temp_dir.path().join("bin");
+    let config_file_path = temp_dir.path().join("test_tsconfig.json");
+    let config = "{}";
+    fs::write(&config_file_path, config).unwrap();
+
+    install(
+      Flags {
+        config_path: Some(config_file_path.to_string_lossy().to_string()),
+        ..Flags::default()
+      },
+      "http://localhost:4545/cli/tests/cat.ts",
+      vec![],
+      Some("echo_test".to_string()),
+      Some(temp_dir.path().to_path_buf()),
+      false,
+    )
+    .expect("Install failed");
+
+    let file_path = get_executable_path(&bin_dir, "echo_test");
+    assert!(file_path.exists());
+    assert!(bin_dir.join("echo_test.tsconfig.json").exists());
+
+    uninstall("echo_test".to_string(), Some(temp_dir.path().to_path_buf()))
+      .expect("Uninstall failed");
+
+    assert!(!file_path.exists());
+    assert!(!file_path.with_extension("").exists());
+    assert!(!bin_dir.join("echo_test.tsconfig.json").exists());
+    let manifest = Manifest::read(temp_dir.path()).unwrap();
+    assert!(manifest.scripts.is_empty());
+
+    let result =
+      uninstall("echo_test".to_string(), Some(temp_dir.path().to_path_buf()));
+    assert!(result
+      .unwrap_err()
+      .to_string()
+      .contains("No installation found for echo_test"));
+  }
+
   #[test]
   fn install_with_config() {
     let temp_dir = TempDir::new().expect("tempdir fail");
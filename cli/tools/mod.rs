@@ -1,5 +1,6 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
+pub mod cache;
 pub mod coverage;
 pub mod doc;
 pub mod fmt;
@@ -6,9 +6,11 @@ use crate::colors;
 use crate::media_type::MediaType;
 use crate::program_state::ProgramState;
 use deno_core::error::AnyError;
+use deno_core::resolve_url_or_path;
 use deno_core::serde_json::json;
 use deno_core::serde_json::Value;
 use deno_runtime::inspector::InspectorSession;
+use deno_runtime::permissions::Permissions;
 use deno_runtime::worker::MainWorker;
 use rustyline::completion::Completer;
 use rustyline::error::ReadlineError;
@@ -286,9 +288,11 @@ async fn read_line_and_poll(
   message_rx: &Receiver<(String, Option<Value>)>,
   response_tx: &Sender<Result<Value, AnyError>>,
   editor: Arc<Mutex<Editor<Helper>>>,
+  prompt: &'static str,
 ) -> Result<String, ReadlineError> {
-  let mut line =
-    tokio::task::spawn_blocking(move || editor.lock().unwrap().readline("> "));
+  let mut line = tokio::task::spawn_blocking(move || {
+    editor.lock().unwrap().readline(prompt)
+  });
 
   let mut poll_worker = true;
 
@@ -351,6 +355,28 @@ Object.defineProperty(globalThis, "_error", {
 });
 "#;
 
+/// Transpiles a REPL input from TypeScript into a script which can be
+/// evaluated by the inspector, rewriting its imports into dynamic imports.
+///
+/// Inputs which fail to parse are returned as is, so the syntax error is
+/// reported by the evaluation itself.
+fn transpile_input(input: &str) -> String {
+  let parsed_module =
+    match ast::parse("$deno$repl.ts", input, &MediaType::TypeScript) {
+      Ok(parsed_module) => parsed_module,
+      Err(_) => return input.to_string(),
+    };
+  let emit_options = ast::EmitOptions {
+    inline_source_map: false,
+    repl_imports: true,
+    ..ast::EmitOptions::default()
+  };
+  match parsed_module.transpile(&emit_options) {
+    Ok((code, _)) => code,
+    Err(_) => input.to_string(),
+  }
+}
+
 async fn inject_prelude(
   worker: &mut MainWorker,
   session: &mut InspectorSession,
@@ -395,17 +421,111 @@ pub async fn is_closing(
   Ok(closed)
 }
 
-pub async fn run(
-  program_state: &ProgramState,
-  mut worker: MainWorker,
-) -> Result<(), AnyError> {
-  let mut session = worker.create_inspector_session();
+/// The commands which are accepted at the prompt besides JavaScript, with the
+/// description printed by `.help`.
+static COMMANDS: &[(&str, &str)] = &[
+  (".clear", "Reset the REPL context"),
+  (
+    ".editor",
+    "Enter editor mode, evaluating the input on ctrl+d or cancelling it on ctrl+c",
+  ),
+  (".help", "Print this list of commands"),
+  (".load", "Evaluate a file in the REPL context"),
+  (".save", "Save the inputs of the current session to a file"),
+];
+
+/// Splits a line into one of the `COMMANDS` and its argument, if it starts
+/// with a command. Other lines, such as `.5 + 1`, are left to be evaluated.
+fn parse_command(line: &str) -> Option<(&str, &str)> {
+  let line = line.trim();
+  let (name, argument) = match line.find(char::is_whitespace) {
+    Some(index) => (&line[..index], line[index..].trim()),
+    None => (line, ""),
+  };
 
-  let history_file = program_state.dir.root.join("deno_history.txt");
+  COMMANDS
+    .iter()
+    .find(|(command, _)| *command == name)
+    .map(|(command, _)| (*command, argument))
+}
 
-  post_message_and_poll(&mut worker, &mut session, "Runtime.enable", None)
+/// Reads a file for `.load` and `--eval-file`, which can be a path relative
+/// to the current directory or a remote URL, and returns the input to
+/// evaluate for it.
+///
+/// The file is fetched with the permissions of the worker. Scripts are
+/// evaluated as is, while modules are dynamically imported so that their own
+/// imports resolve relative to them, and their exports are copied into the
+/// REPL scope.
+async fn read_file(
+  program_state: &ProgramState,
+  worker: &mut MainWorker,
+  path: &str,
+) -> Result<String, AnyError> {
+  let specifier = resolve_url_or_path(path)?;
+  let permissions = {
+    let op_state = worker.js_runtime.op_state();
+    let op_state = op_state.borrow();
+    op_state.borrow::<Permissions>().clone()
+  };
+  let file = program_state
+    .file_fetcher
+    .fetch(&specifier, &permissions)
     .await?;
 
+  let is_module =
+    ast::parse(specifier.as_str(), &file.source, &file.media_type)
+      .map(|parsed_module| parsed_module.has_module_declarations())
+      .unwrap_or(false);
+  if is_module {
+    Ok(format!(
+      "void Object.assign(globalThis, await import({}));",
+      json!(specifier.as_str())
+    ))
+  } else {
+    Ok(file.source)
+  }
+}
+
+/// Reads lines until ctrl+d for `.editor`, returning `None` if the input was
+/// cancelled with ctrl+c.
+async fn read_editor_input(
+  worker: &mut MainWorker,
+  session: &mut InspectorSession,
+  message_rx: &Receiver<(String, Option<Value>)>,
+  response_tx: &Sender<Result<Value, AnyError>>,
+  editor: Arc<Mutex<Editor<Helper>>>,
+) -> Result<Option<String>, AnyError> {
+  println!("// Entering editor mode (ctrl+d to finish, ctrl+c to cancel)");
+
+  let mut lines = Vec::new();
+  loop {
+    let line = read_line_and_poll(
+      worker,
+      session,
+      message_rx,
+      response_tx,
+      editor.clone(),
+      "",
+    )
+    .await;
+    match line {
+      Ok(line) => lines.push(line),
+      Err(ReadlineError::Eof) => return Ok(Some(lines.join("\n"))),
+      Err(ReadlineError::Interrupted) => return Ok(None),
+      Err(err) => return Err(err.into()),
+    }
+  }
+}
+
+/// Enables the runtime domain for a new inspector session and injects the
+/// prelude, returning the id of the execution context to evaluate inputs in.
+async fn initialize_context(
+  worker: &mut MainWorker,
+  session: &mut InspectorSession,
+) -> Result<u64, AnyError> {
+  post_message_and_poll(worker, session, "Runtime.enable", None).await?;
+
   // Enabling the runtime domain will always send trigger one executionContextCreated for each
   // context the inspector knows about so we grab the execution context from that since
   // our inspector does not support a default context (0 is an invalid context id).
@@ -425,6 +545,146 @@ pub async fn run(
     }
   }
 
+  inject_prelude(worker, session, context_id).await?;
+
+  Ok(context_id)
+}
+
+async fn evaluate_input(
+  worker: &mut MainWorker,
+  session: &mut InspectorSession,
+  context_id: u64,
+  input: &str,
+) -> Result<Value, AnyError> {
+  // It is a bit unexpected that { "foo": "bar" } is interpreted as a block
+  // statement rather than an object literal so we interpret it as an expression statement
+  // to match the behavior found in a typical prompt including browser developer tools.
+  let wrapped_input = if input.trim_start().starts_with('{')
+    && !input.trim_end().ends_with(';')
+  {
+    format!("({})", input)
+  } else {
+    input.to_string()
+  };
+
+  let evaluate_response = post_message_and_poll(
+    worker,
+    session,
+    "Runtime.evaluate",
+    Some(json!({
+      "expression": format!(
+        "'use strict'; void 0;\n{}",
+        transpile_input(&wrapped_input)
+      ),
+      "contextId": context_id,
+      "replMode": true,
+    })),
+  )
+  .await?;
+
+  // If that fails, we retry it without wrapping in parens letting the error bubble up to the
+  // user if it is still an error.
+  if evaluate_response.get("exceptionDetails").is_some()
+    && wrapped_input != input
+  {
+    post_message_and_poll(
+      worker,
+      session,
+      "Runtime.evaluate",
+      Some(json!({
+        "expression": format!(
+          "'use strict'; void 0;\n{}",
+          transpile_input(input)
+        ),
+        "contextId": context_id,
+        "replMode": true,
+      })),
+    )
+    .await
+  } else {
+    Ok(evaluate_response)
+  }
+}
+
+async fn print_evaluate_response(
+  worker: &mut MainWorker,
+  session: &mut InspectorSession,
+  context_id: u64,
+  evaluate_response: &Value,
+) -> Result<(), AnyError> {
+  let evaluate_result = evaluate_response.get("result").unwrap();
+  let evaluate_exception_details = evaluate_response.get("exceptionDetails");
+
+  if evaluate_exception_details.is_some() {
+    post_message_and_poll(
+              worker,
+              session,
+              "Runtime.callFunctionOn",
+              Some(json!({
+                "executionContextId": context_id,
+                "functionDeclaration": "function (object) { Deno[Deno.internal].lastThrownError = object; }",
+                "arguments": [
+                  evaluate_result,
+                ],
+              })),
+            ).await?;
+  } else {
+    post_message_and_poll(
+              worker,
+              session,
+              "Runtime.callFunctionOn",
+              Some(json!({
+                "executionContextId": context_id,
+                "functionDeclaration": "function (object) { Deno[Deno.internal].lastEvalResult = object; }",
+                "arguments": [
+                  evaluate_result,
+                ],
+              })),
+            ).await?;
+  }
+
+  // TODO(caspervonb) we should investigate using previews here but to keep things
+  // consistent with the previous implementation we just get the preview result from
+  // Deno.inspectArgs.
+  let inspect_response =
+    post_message_and_poll(
+      worker,
+      session,
+      "Runtime.callFunctionOn",
+      Some(json!({
+        "executionContextId": context_id,
+        "functionDeclaration": "function (object) { return Deno[Deno.internal].inspectArgs(['%o', object], { colors: !Deno.noColor }); }",
+        "arguments": [
+          evaluate_result,
+        ],
+      })),
+    ).await?;
+
+  let inspect_result = inspect_response.get("result").unwrap();
+
+  let value = inspect_result.get("value").unwrap().as_str().unwrap();
+  let output = match evaluate_exception_details {
+    Some(_) => format!("Uncaught {}", value),
+    None => value.to_string(),
+  };
+
+  println!("{}", output);
+
+  Ok(())
+}
+
+pub async fn run(
+  program_state: &ProgramState,
+  mut worker: MainWorker,
+  create_worker: &dyn Fn() -> MainWorker,
+  maybe_eval_files: Option<Vec<String>>,
+) -> Result<(), AnyError> {
+  let mut session = worker.create_inspector_session();
+
+  let history_file = program_state.dir.root.join("deno_history.txt");
+
+  let mut context_id = initialize_context(&mut worker, &mut session).await?;
+
   let (message_tx, message_rx) = sync_channel(1);
   let (response_tx, response_rx) = channel();
 
@@ -447,7 +707,32 @@ pub async fn run(
   println!("Deno {}", crate::version::deno());
   println!("exit using ctrl+d or close()");
 
-  inject_prelude(&mut worker, &mut session, context_id).await?;
+  for eval_file in maybe_eval_files.unwrap_or_default() {
+    match read_file(program_state, &mut worker, &eval_file).await {
+      Ok(source) => {
+        let evaluate_response =
+          evaluate_input(&mut worker, &mut session, context_id, &source)
+            .await?;
+        // Only errors are printed, as the result of a prelude isn't of
+        // interest.
+        if evaluate_response.get("exceptionDetails").is_some() {
+          print_evaluate_response(
+            &mut worker,
+            &mut session,
+            context_id,
+            &evaluate_response,
+          )
+          .await?;
+        }
+      }
+      Err(err) => {
+        println!("Error in --eval-file file {}: {}", eval_file, err);
+      }
+    }
+  }
+
+  // The inputs evaluated in this session, which are written by `.save`.
+  let mut session_inputs: Vec<String> = Vec::new();
 
   loop {
     let line = read_line_and_poll(
@@ -456,53 +741,77 @@ pub async fn run(
       &message_rx,
       &response_tx,
       editor.clone(),
+      "> ",
     )
     .await;
     match line {
       Ok(line) => {
-        // It is a bit unexpected that { "foo": "bar" } is interpreted as a block
-        // statement rather than an object literal so we interpret it as an expression statement
-        // to match the behavior found in a typical prompt including browser developer tools.
-        let wrapped_line = if line.trim_start().starts_with('{')
-          && !line.trim_end().ends_with(';')
-        {
-          format!("({})", &line)
-        } else {
-          line.clone()
-        };
-
-        let evaluate_response = post_message_and_poll(
-          &mut worker,
-          &mut session,
-          "Runtime.evaluate",
-          Some(json!({
-            "expression": format!("'use strict'; void 0;\n{}", &wrapped_line),
-            "contextId": context_id,
-            "replMode": true,
-          })),
-        )
-        .await?;
+        editor.lock().unwrap().add_history_entry(line.as_str());
 
-        // If that fails, we retry it without wrapping in parens letting the error bubble up to the
-        // user if it is still an error.
-        let evaluate_response =
-          if evaluate_response.get("exceptionDetails").is_some()
-            && wrapped_line != line
-          {
-            post_message_and_poll(
+        let input = match parse_command(&line) {
+          Some((".clear", _)) => {
+            println!("Clearing context...");
+            // The session has to be dropped before the worker it belongs to.
+            drop(session);
+            worker = create_worker();
+            worker.run_event_loop().await?;
+            session = worker.create_inspector_session();
+            context_id = initialize_context(&mut worker, &mut session).await?;
+            if let Some(helper) = editor.lock().unwrap().helper_mut() {
+              helper.context_id = context_id;
+            }
+            session_inputs.clear();
+            continue;
+          }
+          Some((".editor", _)) => {
+            match read_editor_input(
               &mut worker,
               &mut session,
-              "Runtime.evaluate",
-              Some(json!({
-                "expression": format!("'use strict'; void 0;\n{}", &line),
-                "contextId": context_id,
-                "replMode": true,
-              })),
+              &message_rx,
+              &response_tx,
+              editor.clone(),
             )
             .await?
-          } else {
-            evaluate_response
-          };
+            {
+              Some(input) => input,
+              None => continue,
+            }
+          }
+          Some((".help", _)) => {
+            for (command, description) in COMMANDS {
+              println!("{:<9}{}", command, description);
+            }
+            continue;
+          }
+          Some((".load", "")) | Some((".save", "")) => {
+            println!("Error: a file path is required");
+            continue;
+          }
+          Some((".load", path)) => {
+            match read_file(program_state, &mut worker, path).await {
+              Ok(source) => source,
+              Err(err) => {
+                println!("Error: {}", err);
+                continue;
+              }
+            }
+          }
+          Some((".save", path)) => {
+            let mut contents = session_inputs.join("\n");
+            contents.push('\n');
+            match std::fs::write(path, contents) {
+              Ok(()) => println!("Session saved to: {}", path),
+              Err(err) => println!("Error: {}", err),
+            }
+            continue;
+          }
+          Some((command, _)) => unreachable!("unknown command {}", command),
+          None => line,
+        };
+
+        let evaluate_response =
+          evaluate_input(&mut worker, &mut session, context_id, &input).await?;
+        session_inputs.push(input);
 
         // We check for close and break here instead of making it a loop condition to get
         // consistent behavior in when the user evaluates a call to close().
@@ -510,66 +819,13 @@ pub async fn run(
           break;
         }
 
-        let evaluate_result = evaluate_response.get("result").unwrap();
-        let evaluate_exception_details =
-          evaluate_response.get("exceptionDetails");
-
-        if evaluate_exception_details.is_some() {
-          post_message_and_poll(
-                    &mut worker,
-                    &mut session,
-                    "Runtime.callFunctionOn",
-                    Some(json!({
-                      "executionContextId": context_id,
-                      "functionDeclaration": "function (object) { Deno[Deno.internal].lastThrownError = object; }",
-                      "arguments": [
-                        evaluate_result,
-                      ],
-                    })),
-                  ).await?;
-        } else {
-          post_message_and_poll(
-                    &mut worker,
-                    &mut session,
-                    "Runtime.callFunctionOn",
-                    Some(json!({
-                      "executionContextId": context_id,
-                      "functionDeclaration": "function (object) { Deno[Deno.internal].lastEvalResult = object; }",
-                      "arguments": [
-                        evaluate_result,
-                      ],
-                    })),
-                  ).await?;
-        }
-
-        // TODO(caspervonb) we should investigate using previews here but to keep things
-        // consistent with the previous implementation we just get the preview result from
-        // Deno.inspectArgs.
-        let inspect_response =
-          post_message_and_poll(
-            &mut worker,
-            &mut session,
-            "Runtime.callFunctionOn",
-            Some(json!({
-              "executionContextId": context_id,
-              "functionDeclaration": "function (object) { return Deno[Deno.internal].inspectArgs(['%o', object], { colors: !Deno.noColor }); }",
-              "arguments": [
-                evaluate_result,
-              ],
-            })),
-          ).await?;
-
-        let inspect_result = inspect_response.get("result").unwrap();
-
-        let value = inspect_result.get("value").unwrap().as_str().unwrap();
-        let output = match evaluate_exception_details {
-          Some(_) => format!("Uncaught {}", value),
-          None => value.to_string(),
-        };
-
-        println!("{}", output);
-
-        editor.lock().unwrap().add_history_entry(line.as_str());
+        print_evaluate_response(
+          &mut worker,
+          &mut session,
+          context_id,
+          &evaluate_response,
+        )
+        .await?;
       }
       Err(ReadlineError::Interrupted) => {
         println!("exit using ctrl+d or close()");
@@ -3,10 +3,17 @@
 use crate::deno_dir::DenoDir;
 use crate::flags::DenoSubcommand;
 use crate::flags::Flags;
+use crate::fs_util::resolve_from_cwd;
+use crate::module_graph::RuntimeModule;
 use deno_core::error::bail;
+use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::serde_json;
+use deno_core::serde_json::Value;
+use deno_core::url::Url;
+use deno_core::ModuleSpecifier;
 use deno_runtime::deno_fetch::reqwest::Client;
+use std::collections::HashMap;
 use std::env;
 use std::fs::read;
 use std::fs::File;
@@ -14,9 +21,13 @@ use std::io::Read;
 use std::io::Seek;
 use std::io::SeekFrom;
 use std::io::Write;
+use std::path::Component;
 use std::path::Path;
 use std::path::PathBuf;
+use walkdir::WalkDir;
 
+use crate::standalone::EmbeddedModule;
+use crate::standalone::EmbeddedModules;
 use crate::standalone::Metadata;
 use crate::standalone::MAGIC_TRAILER;
 
@@ -85,19 +96,205 @@ async fn download_base_binary(
   Ok(())
 }
 
-/// This functions creates a standalone deno binary by appending a bundle
-/// and magic trailer to the currently executing binary.
-pub fn create_standalone_binary(
-  mut original_bin: Vec<u8>,
-  source_code: String,
+/// The URL the compile root is mapped to in a standalone binary, so that the
+/// binary does not contain paths of the machine it was compiled on.
+pub const EMBEDDED_ROOT_URL: &str = "file:///$deno$/";
+
+/// Find the compile root of a standalone binary, which is the closest
+/// directory containing every local module of the graph and every included
+/// file.
+pub fn compile_root(
+  runtime_modules: &[RuntimeModule],
+  included_files: &[PathBuf],
+) -> Option<PathBuf> {
+  let module_paths = runtime_modules
+    .iter()
+    .filter_map(|module| module.specifier.to_file_path().ok());
+  let mut maybe_root: Option<PathBuf> = None;
+  for path in module_paths.chain(included_files.iter().cloned()) {
+    let dir = match path.parent() {
+      Some(dir) => dir,
+      None => continue,
+    };
+    maybe_root = Some(match maybe_root {
+      Some(root) => root
+        .components()
+        .zip(dir.components())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a)
+        .collect(),
+      None => dir.to_path_buf(),
+    });
+  }
+  // Modules on different drives on Windows have no common directory.
+  maybe_root.filter(|root| root.has_root())
+}
+
+/// Map a local specifier below the compile root to the same path below
+/// `EMBEDDED_ROOT_URL`. Other specifiers are returned unchanged.
+pub fn embedded_specifier(
+  specifier: &ModuleSpecifier,
+  maybe_root: Option<&Path>,
+) -> ModuleSpecifier {
+  let maybe_relative = maybe_root.and_then(|root| {
+    let path = specifier.to_file_path().ok()?;
+    path.strip_prefix(root).ok().map(|p| p.to_path_buf())
+  });
+  match maybe_relative {
+    Some(relative) => {
+      let mut embedded = Url::parse(EMBEDDED_ROOT_URL).unwrap();
+      embedded
+        .path_segments_mut()
+        .unwrap()
+        .pop_if_empty()
+        .extend(path_segments(&relative));
+      embedded
+    }
+    None => specifier.clone(),
+  }
+}
+
+fn path_segments(path: &Path) -> Vec<String> {
+  path
+    .components()
+    .filter_map(|component| match component {
+      Component::Normal(segment) => Some(segment.to_string_lossy().to_string()),
+      _ => None,
+    })
+    .collect()
+}
+
+/// Point the sources of a source map at the embedded specifiers.
+fn embed_source_map(map: String, maybe_root: Option<&Path>) -> String {
+  let mut value: Value = match serde_json::from_str(&map) {
+    Ok(value) => value,
+    Err(_) => return map,
+  };
+  if let Some(sources) = value.get_mut("sources").and_then(|s| s.as_array_mut())
+  {
+    for source in sources.iter_mut() {
+      if let Some(specifier) = source.as_str().and_then(|s| Url::parse(s).ok())
+      {
+        *source =
+          Value::String(embedded_specifier(&specifier, maybe_root).to_string());
+      }
+    }
+  }
+  value.to_string()
+}
+
+/// Convert the modules of a module graph into the form in which they are
+/// embedded in a standalone binary. Local modules are moved below
+/// `EMBEDDED_ROOT_URL`.
+pub fn embed_modules(
+  runtime_modules: Vec<RuntimeModule>,
+  redirects: HashMap<ModuleSpecifier, ModuleSpecifier>,
+  maybe_root: Option<&Path>,
+) -> EmbeddedModules {
+  let embed = |specifier: &ModuleSpecifier| {
+    embedded_specifier(specifier, maybe_root).to_string()
+  };
+  EmbeddedModules {
+    modules: runtime_modules
+      .into_iter()
+      .map(|module| {
+        (
+          embed(&module.specifier),
+          EmbeddedModule {
+            code: module.code,
+            map: module
+              .maybe_map
+              .map(|map| embed_source_map(map, maybe_root)),
+            dependencies: module
+              .dependencies
+              .into_iter()
+              .map(|(specifier, resolved)| {
+                (specifier, embedded_specifier(&resolved, maybe_root))
+              })
+              .collect(),
+          },
+        )
+      })
+      .collect(),
+    redirects: redirects
+      .iter()
+      .map(|(from, to)| (embed(from), embed(to)))
+      .collect(),
+  }
+}
+
+/// Collect the files matched by the `--include` arguments of `deno compile`.
+/// Each argument is either a path to a file, a path to a directory whose files
+/// are included recursively, or a glob pattern. The returned paths are
+/// absolute.
+pub fn collect_included_files(
+  includes: &[String],
+) -> Result<Vec<PathBuf>, AnyError> {
+  let mut files = Vec::new();
+  for include in includes {
+    let path = resolve_from_cwd(Path::new(include))?;
+    let matched = if is_glob(include) {
+      let pattern = path.to_string_lossy();
+      glob::glob(&pattern)
+        .map_err(|err| {
+          generic_error(format!(
+            "Could not include \"{}\": {}.",
+            include, err.msg
+          ))
+        })?
+        .filter_map(|entry| entry.ok())
+        .filter(|file| file.is_file())
+        .collect::<Vec<_>>()
+    } else if path.is_dir() {
+      walk_files(&path)
+    } else if path.is_file() {
+      vec![path]
+    } else {
+      return Err(generic_error(format!(
+        "Could not include \"{}\": no such file or directory.",
+        include
+      )));
+    };
+    if matched.is_empty() {
+      return Err(generic_error(format!(
+        "Could not include \"{}\": no files matched.",
+        include
+      )));
+    }
+    for file in matched {
+      if !files.contains(&file) {
+        files.push(file);
+      }
+    }
+  }
+  Ok(files)
+}
+
+fn is_glob(include: &str) -> bool {
+  include.contains(|c| c == '*' || c == '?' || c == '[')
+}
+
+fn walk_files(root: &Path) -> Vec<PathBuf> {
+  WalkDir::new(root)
+    .follow_links(true)
+    .into_iter()
+    .filter_map(|entry| entry.ok())
+    .filter(|entry| entry.file_type().is_file())
+    .map(|entry| entry.into_path())
+    .collect()
+}
+
+/// Create the metadata of a standalone binary from the flags it is run with.
+pub fn create_metadata(
+  main_module: ModuleSpecifier,
   flags: Flags,
-) -> Result<Vec<u8>, AnyError> {
-  let mut source_code = source_code.as_bytes().to_vec();
+) -> Result<Metadata, AnyError> {
   let ca_data = match &flags.ca_file {
     Some(ca_file) => Some(read(ca_file)?),
     None => None,
   };
-  let metadata = Metadata {
+  Ok(Metadata {
+    main_module,
     argv: flags.argv.clone(),
     unstable: flags.unstable,
     seed: flags.seed,
@@ -106,18 +303,63 @@ pub fn create_standalone_binary(
     v8_flags: flags.v8_flags.clone(),
     log_level: flags.log_level,
     ca_data,
-  };
+    ca_stores: crate::program_state::ca_stores_from_env(),
+    proxy: flags.proxy.clone(),
+    files: HashMap::new(),
+    snapshot: None,
+    snapshot_modules: Vec::new(),
+  })
+}
+
+/// This functions creates a standalone deno binary by appending the included
+/// files, the snapshot, embedded modules, metadata and magic trailer to the
+/// currently executing binary.
+pub fn create_standalone_binary(
+  mut original_bin: Vec<u8>,
+  mut metadata: Metadata,
+  modules: EmbeddedModules,
+  included_files: Vec<PathBuf>,
+  maybe_root: Option<&Path>,
+  maybe_snapshot: Option<Vec<u8>>,
+) -> Result<Vec<u8>, AnyError> {
+  // The included files and the snapshot are laid out right after the original
+  // binary, so their offsets are known up front.
+  let mut data_section = Vec::new();
+  for path in included_files {
+    // Included files are keyed by their path relative to the compile root.
+    let key = maybe_root
+      .and_then(|root| path.strip_prefix(root).ok())
+      .map(|relative| path_segments(relative).join("/"))
+      .ok_or_else(|| {
+        generic_error(format!(
+          "Could not include \"{}\": it is not on the same drive as the modules.",
+          path.display()
+        ))
+      })?;
+    let contents = read(&path)?;
+    let offset = (original_bin.len() + data_section.len()) as u64;
+    metadata.files.insert(key, (offset, contents.len() as u64));
+    data_section.extend(contents);
+  }
+  if let Some(snapshot) = maybe_snapshot {
+    let offset = (original_bin.len() + data_section.len()) as u64;
+    metadata.snapshot = Some((offset, snapshot.len() as u64));
+    data_section.extend(snapshot);
+  }
+
+  let mut source_code = serde_json::to_string(&modules)?.as_bytes().to_vec();
   let mut metadata = serde_json::to_string(&metadata)?.as_bytes().to_vec();
 
-  let bundle_pos = original_bin.len();
+  let bundle_pos = original_bin.len() + data_section.len();
   let metadata_pos = bundle_pos + source_code.len();
   let mut trailer = MAGIC_TRAILER.to_vec();
   trailer.write_all(&bundle_pos.to_be_bytes())?;
   trailer.write_all(&metadata_pos.to_be_bytes())?;
 
   let mut final_bin =
-    Vec::with_capacity(original_bin.len() + source_code.len() + trailer.len());
+    Vec::with_capacity(metadata_pos + metadata.len() + trailer.len());
   final_bin.append(&mut original_bin);
+  final_bin.append(&mut data_section);
   final_bin.append(&mut source_code);
   final_bin.append(&mut metadata);
   final_bin.append(&mut trailer);
@@ -211,8 +453,10 @@ pub fn compile_to_runtime_flags(
     no_check: false,
     no_prompts: flags.no_prompts,
     no_remote: false,
+    proxy: flags.proxy,
     reload: false,
     repl: false,
+    revalidate: false,
     seed: flags.seed,
     unstable: flags.unstable,
     v8_flags: flags.v8_flags,
@@ -220,3 +464,30 @@ pub fn compile_to_runtime_flags(
     watch: false,
   })
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_embedded_specifier() {
+    let root = env::temp_dir().join("app");
+    let module = |path: &str| RuntimeModule {
+      specifier: Url::from_file_path(root.join(path)).unwrap(),
+      code: String::new(),
+      maybe_map: None,
+      dependencies: HashMap::new(),
+    };
+    let modules = vec![module("src/main.ts"), module("lib/util.ts")];
+    let included_files = vec![root.join("assets/index.html")];
+    let maybe_root = compile_root(&modules, &included_files);
+    assert_eq!(maybe_root, Some(root.clone()));
+    assert_eq!(
+      embedded_specifier(&modules[0].specifier, maybe_root.as_deref()).as_str(),
+      "file:///$deno$/src/main.ts"
+    );
+    let remote = Url::parse("https://deno.land/std/fmt/colors.ts").unwrap();
+    assert_eq!(embedded_specifier(&remote, maybe_root.as_deref()), remote);
+    assert_eq!(compile_root(&[], &[]), None);
+  }
+}
@@ -2,9 +2,13 @@
 
 //! This module provides feature to upgrade deno executable
 
+use crate::colors;
+use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_runtime::deno_fetch::reqwest;
 use deno_runtime::deno_fetch::reqwest::Client;
+use ring::signature::UnparsedPublicKey;
+use ring::signature::ED25519;
 use semver_parser::version::parse as semver_parse;
 use std::fs;
 use std::path::Path;
@@ -17,7 +21,21 @@ lazy_static! {
 }
 
 const RELEASE_URL: &str = "https://github.com/denoland/deno/releases";
+const CANARY_URL: &str = "https://dl.deno.land";
 
+/// The name of the checksum manifest published next to the archives of a
+/// release, in the format of `sha256sum`.
+const CHECKSUM_MANIFEST_NAME: &str = "SHA256SUMS";
+
+/// The name of the detached Ed25519 signature of the checksum manifest.
+const CHECKSUM_SIGNATURE_NAME: &str = "SHA256SUMS.sig";
+
+/// The base64 encoded Ed25519 public key which the signature of the checksum
+/// manifest is verified against. No release signing key is published yet, so
+/// signatures are only verified once one is pinned here.
+const UPGRADE_PUBLIC_KEY: Option<&str> = None;
+
+#[allow(clippy::too_many_arguments)]
 pub async fn upgrade_command(
   dry_run: bool,
   force: bool,
@@ -25,7 +43,13 @@ pub async fn upgrade_command(
   version: Option<String>,
   output: Option<PathBuf>,
   ca_file: Option<String>,
+  mirror: Option<String>,
+  from_file: Option<PathBuf>,
 ) -> Result<(), AnyError> {
+  if let Some(from_file) = from_file {
+    return upgrade_from_file(dry_run, force, output, &from_file);
+  }
+
   let mut client_builder = Client::builder();
 
   // If we have been provided a CA Certificate, add it into the HTTP client
@@ -56,9 +80,9 @@ pub async fn upgrade_command(
     }
     None => {
       let latest_version = if canary {
-        get_latest_canary_version(&client).await?
+        get_latest_canary_version(&client, mirror.as_deref()).await?
       } else {
-        get_latest_release_version(&client).await?
+        get_latest_release_version(&client, mirror.as_deref()).await?
       };
 
       let current_is_most_recent = if canary {
@@ -92,48 +116,121 @@ pub async fn upgrade_command(
     }
   };
 
-  let download_url = if canary {
-    format!(
-      "https://dl.deno.land/canary/{}/{}",
-      install_version, *ARCHIVE_NAME
-    )
-  } else {
-    format!(
-      "{}/download/v{}/{}",
-      RELEASE_URL, install_version, *ARCHIVE_NAME
-    )
+  let release_url = match (canary, mirror) {
+    (true, mirror) => format!(
+      "{}/canary/{}",
+      mirror_url(mirror.as_deref().unwrap_or(CANARY_URL)),
+      install_version
+    ),
+    (false, Some(mirror)) => {
+      format!("{}/release/v{}", mirror_url(&mirror), install_version)
+    }
+    (false, None) => format!("{}/download/v{}", RELEASE_URL, install_version),
   };
+  let download_url = format!("{}/{}", release_url, *ARCHIVE_NAME);
 
-  let archive_data = download_package(client, &*download_url).await?;
+  let archive_data = download_package(&client, &*download_url).await?;
+  verify_package(&client, &release_url, &archive_data).await?;
 
   println!("Deno is upgrading to version {}", &install_version);
 
-  let old_exe_path = std::env::current_exe()?;
+  install_package(archive_data, dry_run, output)?;
+
+  println!("Upgraded successfully");
+
+  Ok(())
+}
+
+/// Upgrades from a release archive on disk, which is verified against the
+/// checksum manifest in the same directory. The version is only known once
+/// the archive is unpacked, so it is read from the new executable.
+fn upgrade_from_file(
+  dry_run: bool,
+  force: bool,
+  output: Option<PathBuf>,
+  archive_path: &Path,
+) -> Result<(), AnyError> {
+  println!("Checking {}", archive_path.display());
+
+  let archive_data = fs::read(archive_path).map_err(|err| {
+    generic_error(format!(
+      "Could not read {}: {}",
+      archive_path.display(),
+      err
+    ))
+  })?;
+  verify_local_package(archive_path, &archive_data)?;
+
+  let new_exe_path = unpack_exe(archive_data)?;
+  let install_version = check_exe(&new_exe_path)?;
+
+  if !force && output.is_none() && install_version == crate::version::deno() {
+    println!("Version {} is already installed", crate::version::deno());
+    return Ok(());
+  }
+
+  println!("Deno is upgrading to version {}", &install_version);
+
+  install_exe(&new_exe_path, dry_run, output)?;
+
+  println!("Upgraded successfully");
+
+  Ok(())
+}
+
+fn install_package(
+  archive_data: Vec<u8>,
+  dry_run: bool,
+  output: Option<PathBuf>,
+) -> Result<(), AnyError> {
+  let new_exe_path = unpack_exe(archive_data)?;
+  check_exe(&new_exe_path)?;
+  install_exe(&new_exe_path, dry_run, output)
+}
+
+/// Unpacks the executable with the permissions of the current one.
+fn unpack_exe(archive_data: Vec<u8>) -> Result<PathBuf, AnyError> {
   let new_exe_path = unpack(archive_data, "deno", cfg!(windows))?;
-  let permissions = fs::metadata(&old_exe_path)?.permissions();
+  let permissions = fs::metadata(std::env::current_exe()?)?.permissions();
   fs::set_permissions(&new_exe_path, permissions)?;
-  check_exe(&new_exe_path)?;
+  Ok(new_exe_path)
+}
 
+fn install_exe(
+  new_exe_path: &Path,
+  dry_run: bool,
+  output: Option<PathBuf>,
+) -> Result<(), AnyError> {
   if !dry_run {
     match output {
       Some(path) => {
-        fs::rename(&new_exe_path, &path)
-          .or_else(|_| fs::copy(&new_exe_path, &path).map(|_| ()))?;
+        fs::rename(new_exe_path, &path)
+          .or_else(|_| fs::copy(new_exe_path, &path).map(|_| ()))?;
       }
-      None => replace_exe(&new_exe_path, &old_exe_path)?,
+      None => replace_exe(new_exe_path, &std::env::current_exe()?)?,
     }
   }
 
-  println!("Upgraded successfully");
-
   Ok(())
 }
 
+fn mirror_url(mirror: &str) -> &str {
+  mirror.trim_end_matches('/')
+}
+
 async fn get_latest_release_version(
   client: &Client,
+  mirror: Option<&str>,
 ) -> Result<String, AnyError> {
   println!("Looking up latest version");
 
+  if let Some(mirror) = mirror {
+    let url = format!("{}/release-latest.txt", mirror_url(mirror));
+    let version = download_file(client, &url).await?;
+    let version = String::from_utf8_lossy(&version);
+    return Ok(version.trim().trim_start_matches('v').to_string());
+  }
+
   let res = client
     .get(&format!("{}/latest", RELEASE_URL))
     .send()
@@ -145,20 +242,22 @@ async fn get_latest_release_version(
 
 async fn get_latest_canary_version(
   client: &Client,
+  mirror: Option<&str>,
 ) -> Result<String, AnyError> {
   println!("Looking up latest version");
 
-  let res = client
-    .get("https://dl.deno.land/canary-latest.txt")
-    .send()
-    .await?;
+  let url = format!(
+    "{}/canary-latest.txt",
+    mirror_url(mirror.unwrap_or(CANARY_URL))
+  );
+  let res = client.get(&url).send().await?;
   let version = res.text().await?.trim().to_string();
 
   Ok(version)
 }
 
 async fn download_package(
-  client: Client,
+  client: &Client,
   download_url: &str,
 ) -> Result<Vec<u8>, AnyError> {
   println!("Checking {}", &download_url);
@@ -174,6 +273,213 @@ async fn download_package(
   }
 }
 
+async fn download_file(
+  client: &Client,
+  download_url: &str,
+) -> Result<Vec<u8>, AnyError> {
+  download_optional_file(client, download_url)
+    .await?
+    .ok_or_else(|| {
+      generic_error(format!("Could not download {}: not found", download_url))
+    })
+}
+
+/// Like `download_file`, but returns `None` if the file doesn't exist.
+async fn download_optional_file(
+  client: &Client,
+  download_url: &str,
+) -> Result<Option<Vec<u8>>, AnyError> {
+  let res = client.get(download_url).send().await?;
+
+  if res.status().is_success() {
+    Ok(Some(res.bytes().await?.to_vec()))
+  } else if res.status() == reqwest::StatusCode::NOT_FOUND {
+    Ok(None)
+  } else {
+    Err(generic_error(format!(
+      "Could not download {}: {}",
+      download_url,
+      res.status()
+    )))
+  }
+}
+
+/// Verifies the downloaded archive against the checksum manifest of the
+/// release, and the manifest against its signature when a public key is
+/// pinned.
+async fn verify_package(
+  client: &Client,
+  release_url: &str,
+  archive_data: &[u8],
+) -> Result<(), AnyError> {
+  let manifest_url = format!("{}/{}", release_url, CHECKSUM_MANIFEST_NAME);
+  let manifest = download_optional_file(client, &manifest_url).await?;
+  let signature = match (&manifest, UPGRADE_PUBLIC_KEY) {
+    (Some(_), Some(_)) => {
+      let signature_url =
+        format!("{}/{}", release_url, CHECKSUM_SIGNATURE_NAME);
+      download_optional_file(client, &signature_url).await?
+    }
+    _ => None,
+  };
+
+  verify_manifest(
+    &*ARCHIVE_NAME,
+    archive_data,
+    manifest.as_deref(),
+    &manifest_url,
+    signature.as_deref(),
+    UPGRADE_PUBLIC_KEY,
+  )
+}
+
+/// Like `verify_package`, but reads the checksum manifest and its signature
+/// from the directory of an archive on disk.
+fn verify_local_package(
+  archive_path: &Path,
+  archive_data: &[u8],
+) -> Result<(), AnyError> {
+  let archive_dir = archive_path.parent().unwrap_or_else(|| Path::new("."));
+  let read_file = |path: &Path| match fs::read(path) {
+    Ok(data) => Ok(Some(data)),
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+    Err(err) => Err(generic_error(format!(
+      "Could not read {}: {}",
+      path.display(),
+      err
+    ))),
+  };
+  let manifest_path = archive_dir.join(CHECKSUM_MANIFEST_NAME);
+  let manifest = read_file(&manifest_path)?;
+  let signature = match UPGRADE_PUBLIC_KEY {
+    Some(_) => read_file(&archive_dir.join(CHECKSUM_SIGNATURE_NAME))?,
+    None => None,
+  };
+
+  let archive_name = archive_path
+    .file_name()
+    .and_then(|name| name.to_str())
+    .unwrap_or(&*ARCHIVE_NAME);
+  verify_manifest(
+    archive_name,
+    archive_data,
+    manifest.as_deref(),
+    &manifest_path.display().to_string(),
+    signature.as_deref(),
+    UPGRADE_PUBLIC_KEY,
+  )
+}
+
+/// Verifies an archive against a checksum manifest, and the manifest against
+/// its signature if a public key is given. Releases don't publish a manifest
+/// or a signature yet, so either being missing is only warned about.
+fn verify_manifest(
+  archive_name: &str,
+  archive_data: &[u8],
+  manifest: Option<&[u8]>,
+  manifest_location: &str,
+  signature: Option<&[u8]>,
+  public_key: Option<&str>,
+) -> Result<(), AnyError> {
+  let manifest = match manifest {
+    Some(manifest) => manifest,
+    None => {
+      eprintln!(
+        "{} No {} checksum manifest was found at {}, the checksum of {} is not verified",
+        colors::yellow("Warning"),
+        CHECKSUM_MANIFEST_NAME,
+        manifest_location,
+        archive_name
+      );
+      return Ok(());
+    }
+  };
+
+  if let Some(public_key) = public_key {
+    match signature {
+      Some(signature) => {
+        verify_signature(manifest, signature, public_key)?;
+        println!("Checksum manifest signature verified");
+      }
+      None => eprintln!(
+        "{} The checksum manifest is not signed ({} is missing), its signature is not verified",
+        colors::yellow("Warning"),
+        CHECKSUM_SIGNATURE_NAME
+      ),
+    }
+  }
+
+  let manifest = std::str::from_utf8(manifest)
+    .map_err(|_| generic_error("Invalid checksum manifest, aborting"))?;
+  verify_checksum(archive_data, archive_name, manifest)?;
+  println!("Checksum of {} verified", archive_name);
+
+  Ok(())
+}
+
+/// Finds the checksum of a file in a manifest in the format of `sha256sum`,
+/// where each line consists of a checksum and a file name, which is prefixed
+/// with `*` for files read in binary mode.
+fn find_checksum<'a>(manifest: &'a str, file_name: &str) -> Option<&'a str> {
+  manifest.lines().find_map(|line| {
+    let mut parts = line.split_whitespace();
+    let checksum = parts.next()?;
+    let name = parts.next()?;
+    if name.trim_start_matches('*') == file_name {
+      Some(checksum)
+    } else {
+      None
+    }
+  })
+}
+
+fn verify_checksum(
+  data: &[u8],
+  file_name: &str,
+  manifest: &str,
+) -> Result<(), AnyError> {
+  let expected = find_checksum(manifest, file_name).ok_or_else(|| {
+    generic_error(format!(
+      "The checksum manifest has no entry for {}, aborting",
+      file_name
+    ))
+  })?;
+  let actual = crate::checksum::gen(&[data]);
+  if !expected.eq_ignore_ascii_case(&actual) {
+    return Err(generic_error(format!(
+      "Checksum verification of {} failed, aborting\n  expected: {}\n  actual: {}",
+      file_name, expected, actual
+    )));
+  }
+  Ok(())
+}
+
+/// Verifies a detached Ed25519 signature, either raw or base64 encoded,
+/// against a base64 encoded public key.
+fn verify_signature(
+  data: &[u8],
+  signature: &[u8],
+  public_key: &str,
+) -> Result<(), AnyError> {
+  let public_key = base64::decode(public_key.trim())
+    .map_err(|_| generic_error("Invalid pinned upgrade public key"))?;
+  let signature = if signature.len() == 64 {
+    signature.to_vec()
+  } else {
+    let signature = std::str::from_utf8(signature).unwrap_or_default();
+    base64::decode(signature.trim()).map_err(|_| {
+      generic_error("Invalid signature of the checksum manifest, aborting")
+    })?
+  };
+  UnparsedPublicKey::new(&ED25519, public_key)
+    .verify(data, &signature)
+    .map_err(|_| {
+      generic_error(
+        "Signature verification of the checksum manifest failed, aborting",
+      )
+    })
+}
+
 pub fn unpack(
   archive_data: Vec<u8>,
   exe_name: &str,
@@ -233,25 +539,178 @@ pub fn unpack(
   Ok(exe_path)
 }
 
-fn replace_exe(new: &Path, old: &Path) -> Result<(), std::io::Error> {
-  if cfg!(windows) {
-    // On windows you cannot replace the currently running executable.
-    // so first we rename it to deno.old.exe
-    fs::rename(old, old.with_extension("old.exe"))?;
+/// Replaces the current executable, restoring it if the new one can't be put
+/// in its place or fails to run from there.
+fn replace_exe(new: &Path, old: &Path) -> Result<(), AnyError> {
+  // On windows you cannot replace the currently running executable, so in
+  // any case it is renamed to deno.old.exe first, which also keeps a backup.
+  let backup = if cfg!(windows) {
+    old.with_extension("old.exe")
   } else {
-    fs::remove_file(old)?;
-  }
+    old.with_extension("old")
+  };
+  fs::rename(old, &backup)?;
+
   // Windows cannot rename files across device boundaries, so if rename fails,
   // we try again with copy.
-  fs::rename(new, old).or_else(|_| fs::copy(new, old).map(|_| ()))?;
+  let result = fs::rename(new, old)
+    .or_else(|_| fs::copy(new, old).map(|_| ()))
+    .map_err(AnyError::from)
+    .and_then(|_| check_exe(old).map(|_| ()));
+  if let Err(err) = result {
+    let _ = fs::remove_file(old);
+    fs::rename(&backup, old)?;
+    return Err(generic_error(format!(
+      "Failed to replace the executable, the previous version was restored: {}",
+      err
+    )));
+  }
+
+  if !cfg!(windows) {
+    fs::remove_file(&backup)?;
+  }
   Ok(())
 }
 
-fn check_exe(exe_path: &Path) -> Result<(), AnyError> {
+/// Runs the executable with `-V` and returns the version it reports.
+fn check_exe(exe_path: &Path) -> Result<String, AnyError> {
   let output = Command::new(exe_path)
     .arg("-V")
     .stderr(std::process::Stdio::inherit())
     .output()?;
-  assert!(output.status.success());
-  Ok(())
+  if !output.status.success() {
+    return Err(generic_error(format!(
+      "{} -V exited with {}",
+      exe_path.display(),
+      output.status
+    )));
+  }
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  Ok(stdout.trim().trim_start_matches("deno ").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ring::rand::SystemRandom;
+  use ring::signature::Ed25519KeyPair;
+  use ring::signature::KeyPair;
+
+  #[test]
+  fn test_find_checksum() {
+    let manifest = "\
+0123abcd  deno-x86_64-unknown-linux-gnu.zip
+4567ef01 *deno-x86_64-pc-windows-msvc.zip
+";
+    assert_eq!(
+      find_checksum(manifest, "deno-x86_64-unknown-linux-gnu.zip"),
+      Some("0123abcd")
+    );
+    assert_eq!(
+      find_checksum(manifest, "deno-x86_64-pc-windows-msvc.zip"),
+      Some("4567ef01")
+    );
+    assert_eq!(
+      find_checksum(manifest, "deno-x86_64-apple-darwin.zip"),
+      None
+    );
+  }
+
+  #[test]
+  fn test_verify_checksum() {
+    let manifest = "\
+B94D27B9934D3E08A52E52D7DA7DABFAC484EFE37A5380EE9088F7ACE2EFCDE9  deno.zip
+";
+    assert!(verify_checksum(b"hello world", "deno.zip", manifest).is_ok());
+    let err = verify_checksum(b"hello deno", "deno.zip", manifest).unwrap_err();
+    assert!(err
+      .to_string()
+      .starts_with("Checksum verification of deno.zip failed"));
+    let err =
+      verify_checksum(b"hello world", "other.zip", manifest).unwrap_err();
+    assert!(err
+      .to_string()
+      .starts_with("The checksum manifest has no entry for other.zip"));
+  }
+
+  #[test]
+  fn test_verify_signature() {
+    let rng = SystemRandom::new();
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+    let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+    let public_key = base64::encode(key_pair.public_key().as_ref());
+    let manifest = b"0123abcd  deno.zip\n";
+    let signature = key_pair.sign(manifest);
+
+    assert!(verify_signature(manifest, signature.as_ref(), &public_key).is_ok());
+    let encoded_signature = base64::encode(signature.as_ref());
+    assert!(verify_signature(
+      manifest,
+      encoded_signature.as_bytes(),
+      &public_key
+    )
+    .is_ok());
+    assert!(verify_signature(
+      b"4567ef01  deno.zip\n",
+      signature.as_ref(),
+      &public_key
+    )
+    .is_err());
+  }
+
+  #[test]
+  fn test_verify_local_package() {
+    let temp_dir = TempDir::new().unwrap();
+    let archive_path = temp_dir.path().join("deno.zip");
+    fs::write(&archive_path, "hello world").unwrap();
+
+    // Without a manifest the archive isn't verified.
+    assert!(verify_local_package(&archive_path, b"hello deno").is_ok());
+
+    fs::write(
+      temp_dir.path().join(CHECKSUM_MANIFEST_NAME),
+      "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9  deno.zip\n",
+    )
+    .unwrap();
+    assert!(verify_local_package(&archive_path, b"hello world").is_ok());
+    assert!(verify_local_package(&archive_path, b"hello deno").is_err());
+  }
+
+  #[test]
+  fn test_verify_manifest_signature() {
+    let rng = SystemRandom::new();
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+    let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+    let public_key = base64::encode(key_pair.public_key().as_ref());
+    let manifest = b"b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9  deno.zip\n";
+    let signature = key_pair.sign(manifest);
+
+    let verify = |signature: Option<&[u8]>| {
+      verify_manifest(
+        "deno.zip",
+        b"hello world",
+        Some(manifest),
+        "SHA256SUMS",
+        signature,
+        Some(public_key.as_str()),
+      )
+    };
+    assert!(verify(Some(signature.as_ref())).is_ok());
+    assert!(verify(None).is_ok());
+    let err = verify(Some(&[0; 64][..])).unwrap_err();
+    assert!(err
+      .to_string()
+      .starts_with("Signature verification of the checksum manifest failed"));
+  }
+
+  #[test]
+  fn test_replace_exe_restores_backup() {
+    let temp_dir = TempDir::new().unwrap();
+    let old = temp_dir.path().join("deno");
+    fs::write(&old, "old").unwrap();
+    let new = temp_dir.path().join("missing");
+
+    assert!(replace_exe(&new, &old).is_err());
+    assert_eq!(fs::read_to_string(&old).unwrap(), "old");
+  }
 }
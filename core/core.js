@@ -33,7 +33,9 @@ SharedQueue Binary Layout
   let sharedBytes;
   let shared32;
 
-  let asyncHandlers;
+  // Handlers may be registered before `init()` in runtimes which will be
+  // snapshotted.
+  const asyncHandlers = [];
 
   let opsCache = {};
   const errorMap = {};
@@ -45,7 +47,6 @@ SharedQueue Binary Layout
     assert(shared32 == null);
     sharedBytes = new Uint8Array(shared);
     shared32 = new Int32Array(shared);
-    asyncHandlers = [];
     // Callers should not call core.recv, use setAsyncHandler.
     recv(handleAsyncMsgFromRust);
   }
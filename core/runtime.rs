@@ -426,6 +426,9 @@ impl JsRuntime {
     // Note: create_blob() method must not be called from within a HandleScope.
     // TODO(piscisaureus): The rusty_v8 type system should enforce this.
     state.borrow_mut().global_context.take();
+    // Handles held by the runtime aren't part of the snapshot, the macrotask
+    // callback has to be set again once the snapshot is loaded.
+    state.borrow_mut().js_macrotask_cb.take();
 
     std::mem::take(&mut state.borrow_mut().module_map);
 
@@ -1409,11 +1412,17 @@ impl JsRuntime {
     // FIXME(bartlomieju): without check above this call would panic
     // because of lazy initialization in core.js. It seems this lazy initialization
     // hides unnecessary complexity.
-    let js_recv_cb_handle = state_rc
-      .borrow()
-      .js_recv_cb
-      .clone()
-      .expect("Deno.core.recv has not been called.");
+    let maybe_js_recv_cb_handle = state_rc.borrow().js_recv_cb.clone();
+    let js_recv_cb_handle = match maybe_js_recv_cb_handle {
+      Some(handle) => handle,
+      // The shared queue is not initialized in runtimes created with
+      // `will_snapshot`, so there is no way to deliver the responses.
+      None => {
+        return Err(generic_error(
+          "Async ops can't complete in a runtime which will be snapshotted.",
+        ))
+      }
+    };
 
     let context = self.global_context();
     let scope = &mut v8::HandleScope::with_context(self.v8_isolate(), context);
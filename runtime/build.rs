@@ -6,6 +6,8 @@ use std::env;
 use std::path::Path;
 use std::path::PathBuf;
 
+mod op_crates;
+
 // TODO(bartlomieju): this module contains a lot of duplicated
 // logic with `cli/build.rs`, factor out to `deno_core`.
 fn create_snapshot(
@@ -13,11 +15,7 @@ fn create_snapshot(
   snapshot_path: &Path,
   files: Vec<PathBuf>,
 ) {
-  deno_web::init(&mut js_runtime);
-  deno_fetch::init(&mut js_runtime);
-  deno_websocket::init(&mut js_runtime);
-  deno_crypto::init(&mut js_runtime);
-  deno_webgpu::init(&mut js_runtime);
+  op_crates::init(&mut js_runtime);
   // TODO(nayeemrmn): https://github.com/rust-lang/cargo/issues/3946 to get the
   // workspace root.
   let display_root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
@@ -65,9 +63,28 @@ fn main() {
   let runtime_snapshot_path = o.join("CLI_SNAPSHOT.bin");
 
   let js_files = get_js_files("js");
+  write_js_files_index(&o.join("RUNTIME_JS_FILES.rs"), &js_files);
   create_runtime_snapshot(&runtime_snapshot_path, js_files);
 }
 
+/// Write an index of the runtime JavaScript files which embeds their sources,
+/// so snapshots which include user code can be created at runtime.
+fn write_js_files_index(index_path: &Path, files: &[PathBuf]) {
+  let display_root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+  let mut index = String::from("&[\n");
+  for file in files {
+    let display_path = file.strip_prefix(display_root).unwrap();
+    let display_path_str = display_path.display().to_string();
+    index.push_str(&format!(
+      "  ({:?}, include_str!({:?})),\n",
+      "deno:".to_string() + &display_path_str.replace('\\', "/"),
+      file.display().to_string(),
+    ));
+  }
+  index.push_str("]\n");
+  std::fs::write(index_path, index).unwrap();
+}
+
 fn get_js_files(d: &str) -> Vec<PathBuf> {
   let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
   let mut js_files = std::fs::read_dir(d)
@@ -26,6 +26,8 @@ async fn main() -> Result<(), AnyError> {
     debug_flag: false,
     unstable: false,
     ca_data: None,
+    ca_stores: None,
+    proxy: None,
     user_agent: "hello_runtime".to_string(),
     seed: None,
     js_error_create_fn: None,
@@ -5,6 +5,11 @@ use deno_core::Snapshot;
 pub static CLI_SNAPSHOT: &[u8] =
   include_bytes!(concat!(env!("OUT_DIR"), "/CLI_SNAPSHOT.bin"));
 
+/// The name and source of each runtime JavaScript file, in the order they are
+/// executed when creating `CLI_SNAPSHOT`.
+pub static RUNTIME_JS_FILES: &[(&str, &str)] =
+  include!(concat!(env!("OUT_DIR"), "/RUNTIME_JS_FILES.rs"));
+
 pub fn deno_isolate_init() -> Snapshot {
   debug!("Deno isolate init with snapshots.");
   let data = CLI_SNAPSHOT;
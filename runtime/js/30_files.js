@@ -103,6 +103,22 @@
       return seekSync(this.rid, offset, whence);
     }
 
+    async lock(exclusive = false) {
+      await core.jsonOpAsync("op_flock_async", { rid: this.rid, exclusive });
+    }
+
+    lockSync(exclusive = false) {
+      core.jsonOpSync("op_flock_sync", { rid: this.rid, exclusive });
+    }
+
+    async unlock() {
+      await core.jsonOpAsync("op_funlock_async", { rid: this.rid });
+    }
+
+    unlockSync() {
+      core.jsonOpSync("op_funlock_sync", { rid: this.rid });
+    }
+
     close() {
       core.close(this.rid);
     }
@@ -374,6 +374,22 @@
     await core.jsonOpAsync("op_fsync_async", { rid });
   }
 
+  function flockSync(rid, exclusive = false) {
+    core.jsonOpSync("op_flock_sync", { rid, exclusive });
+  }
+
+  async function flock(rid, exclusive = false) {
+    await core.jsonOpAsync("op_flock_async", { rid, exclusive });
+  }
+
+  function funlockSync(rid) {
+    core.jsonOpSync("op_funlock_sync", { rid });
+  }
+
+  async function funlock(rid) {
+    await core.jsonOpAsync("op_funlock_async", { rid });
+  }
+
   window.__bootstrap.fs = {
     cwd,
     chdir,
@@ -422,5 +438,9 @@
     fdatasyncSync,
     fsync,
     fsyncSync,
+    flock,
+    flockSync,
+    funlock,
+    funlockSync,
   };
 })(this);
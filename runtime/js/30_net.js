@@ -75,6 +75,31 @@
     closeWrite() {
       return shutdown(this.rid);
     }
+
+    setNoDelay(noDelay = true) {
+      core.jsonOpSync("op_set_nodelay", { rid: this.rid, noDelay });
+    }
+
+    setKeepAlive(interval) {
+      core.jsonOpSync("op_set_keepalive", {
+        rid: this.rid,
+        interval: interval ?? null,
+      });
+    }
+
+    setSendBufferSize(size) {
+      core.jsonOpSync("op_set_buffer_size", {
+        rid: this.rid,
+        sendBufferSize: size,
+      });
+    }
+
+    setRecvBufferSize(size) {
+      core.jsonOpSync("op_set_buffer_size", {
+        rid: this.rid,
+        recvBufferSize: size,
+      });
+    }
   }
 
   class Listener {
@@ -162,6 +187,45 @@
       return opSend(args, p);
     }
 
+    joinMulticastV4(address, networkInterface = "0.0.0.0") {
+      core.jsonOpSync("op_join_multicast", {
+        rid: this.rid,
+        address,
+        interface: networkInterface,
+      });
+    }
+
+    joinMulticastV6(address, networkInterface = 0) {
+      core.jsonOpSync("op_join_multicast", {
+        rid: this.rid,
+        address,
+        interface: networkInterface,
+      });
+    }
+
+    leaveMulticast(address, networkInterface) {
+      core.jsonOpSync("op_leave_multicast", {
+        rid: this.rid,
+        address,
+        interface: networkInterface ?? null,
+      });
+    }
+
+    setMulticastLoopback(loopback = true) {
+      core.jsonOpSync("op_set_multicast_loopback", {
+        rid: this.rid,
+        loopback,
+      });
+    }
+
+    setMulticastTTL(ttl) {
+      core.jsonOpSync("op_set_multicast_ttl", { rid: this.rid, ttl });
+    }
+
+    setBroadcast(broadcast = true) {
+      core.jsonOpSync("op_set_broadcast", { rid: this.rid, broadcast });
+    }
+
     close() {
       core.close(this.rid);
     }
@@ -6,6 +6,8 @@
   const { File } = window.__bootstrap.files;
   const { readAll } = window.__bootstrap.buffer;
   const { assert, pathFromURL } = window.__bootstrap.util;
+  const { build } = window.__bootstrap.build;
+  const base64 = window.__bootstrap.base64;
 
   function opKill(pid, signo) {
     core.jsonOpSync("op_kill", { pid, signo });
@@ -20,9 +22,25 @@
     return core.jsonOpSync("op_run", request);
   }
 
+  function opCommandOutput(rid, stdoutRid, stderrRid) {
+    return core.jsonOpAsync("op_command_output", {
+      rid,
+      stdoutRid,
+      stderrRid,
+    });
+  }
+
+  function opCommandOutputSync(request) {
+    assert(request.cmd.length > 0);
+    return core.jsonOpSync("op_command_output_sync", request);
+  }
+
   async function runStatus(rid) {
     const res = await opRunStatus(rid);
+    return exitStatus(res);
+  }
 
+  function exitStatus(res) {
     if (res.gotSignal) {
       const signal = res.exitSignal;
       return { success: false, code: 128 + signal, signal };
@@ -86,6 +104,45 @@
     }
   }
 
+  class PtyProcess extends Process {
+    constructor(res) {
+      super(res);
+      this.pty = new File(res.ptyRid);
+    }
+
+    resize(cols, rows) {
+      core.jsonOpSync("op_pty_resize", { rid: this.pty.rid, cols, rows });
+    }
+
+    close() {
+      this.pty.close();
+      super.close();
+    }
+  }
+
+  function runPty({
+    cmd,
+    cwd = undefined,
+    env = {},
+    cols = 80,
+    rows = 24,
+    raw = false,
+  }) {
+    assert(cmd.length > 0);
+    if (cmd[0] != null) {
+      cmd[0] = pathFromURL(cmd[0]);
+    }
+    const res = core.jsonOpSync("op_run_pty", {
+      cmd: cmd.map(String),
+      cwd,
+      env: Object.entries(env),
+      cols,
+      rows,
+      raw,
+    });
+    return new PtyProcess(res);
+  }
+
   function isRid(arg) {
     return !isNaN(arg);
   }
@@ -115,9 +172,182 @@
     return new Process(res);
   }
 
+  function commandOutput(res) {
+    return {
+      ...exitStatus(res),
+      stdout: base64.toByteArray(res.stdout),
+      stderr: base64.toByteArray(res.stderr),
+    };
+  }
+
+  function commandArgs(command, {
+    args = [],
+    cwd = undefined,
+    clearEnv = false,
+    env = {},
+    uid = undefined,
+    gid = undefined,
+    stdin,
+    stdout,
+    stderr,
+  }, processGroup) {
+    return {
+      cmd: [command, ...args.map(String)],
+      cwd,
+      clearEnv,
+      env: Object.entries(env),
+      uid,
+      gid,
+      processGroup,
+      stdin,
+      stdout,
+      stderr,
+      stdinRid: 0,
+      stdoutRid: 0,
+      stderrRid: 0,
+    };
+  }
+
+  const wait = Symbol("wait");
+
+  function killProcessGroup(pid) {
+    // The child was started in its own process group, so killing the group
+    // takes down any processes it spawned as well.
+    try {
+      opKill(build.os === "windows" ? pid : -pid, 9);
+    } catch {
+      // The process has already exited.
+    }
+  }
+
+  class ChildProcess {
+    #rid = 0;
+    #signal = null;
+    #abortHandler = null;
+    #waited = false;
+
+    constructor(res, signal) {
+      this.#rid = res.rid;
+      this.pid = res.pid;
+      this.stdin = res.stdinRid > 0 ? new File(res.stdinRid) : null;
+      this.stdout = res.stdoutRid > 0 ? new File(res.stdoutRid) : null;
+      this.stderr = res.stderrRid > 0 ? new File(res.stderrRid) : null;
+
+      if (signal) {
+        this.#signal = signal;
+        this.#abortHandler = () => killProcessGroup(this.pid);
+        signal.addEventListener("abort", this.#abortHandler);
+      }
+    }
+
+    status() {
+      return this[wait](() => runStatus(this.#rid));
+    }
+
+    output() {
+      if (!this.stdout && !this.stderr) {
+        throw new TypeError("Neither stdout nor stderr were piped");
+      }
+      return this[wait](async () => {
+        try {
+          const res = await opCommandOutput(
+            this.#rid,
+            this.stdout?.rid,
+            this.stderr?.rid,
+          );
+          return commandOutput(res);
+        } finally {
+          this.stdout?.close();
+          this.stderr?.close();
+        }
+      });
+    }
+
+    kill(signo = 15) {
+      opKill(this.pid, signo);
+    }
+
+    async [wait](waitForExit) {
+      if (this.#waited) {
+        throw new TypeError("The child process has already been awaited");
+      }
+      this.#waited = true;
+      try {
+        return await waitForExit();
+      } finally {
+        if (this.#signal) {
+          this.#signal.removeEventListener("abort", this.#abortHandler);
+        }
+        core.close(this.#rid);
+      }
+    }
+  }
+
+  function spawnChild(command, options, defaults) {
+    const { signal } = options;
+    if (signal?.aborted) {
+      throw new DOMException("The command was aborted", "AbortError");
+    }
+    const res = opRun(commandArgs(command, {
+      ...defaults,
+      ...options,
+    }, Boolean(signal)));
+    return new ChildProcess(res, signal);
+  }
+
+  class Command {
+    #command = "";
+    #options = {};
+
+    constructor(command, options = {}) {
+      this.#command = pathFromURL(command);
+      this.#options = options;
+    }
+
+    output() {
+      if (this.#options.stdin === "piped") {
+        throw new TypeError(
+          "Piped stdin is not supported by output(), use spawn() instead",
+        );
+      }
+      return spawnChild(this.#command, this.#options, {
+        stdin: "null",
+        stdout: "piped",
+        stderr: "piped",
+      }).output();
+    }
+
+    spawn() {
+      return spawnChild(this.#command, this.#options, {
+        stdin: "inherit",
+        stdout: "inherit",
+        stderr: "inherit",
+      });
+    }
+
+    spawnSync() {
+      if (this.#options.signal) {
+        throw new TypeError("The signal option is not supported by spawnSync");
+      }
+      if (this.#options.stdin === "piped") {
+        throw new TypeError("Piped stdin is not supported by spawnSync");
+      }
+      const res = opCommandOutputSync(commandArgs(this.#command, {
+        stdin: "null",
+        stdout: "piped",
+        stderr: "piped",
+        ...this.#options,
+      }, false));
+      return commandOutput(res);
+    }
+  }
+
   window.__bootstrap.process = {
     run,
     Process,
+    Command,
+    runPty,
+    PtyProcess,
     kill: opKill,
   };
 })(this);
@@ -23,55 +23,131 @@
     return core.jsonOpAsync("op_start_tls", args);
   }
 
+  class TlsConn extends Conn {
+    #peerCertificates = null;
+    #alpnProtocol = null;
+
+    constructor(rid, remoteAddr, localAddr, peerCertificates, alpnProtocol) {
+      super(rid, remoteAddr, localAddr);
+      this.#peerCertificates = peerCertificates ?? null;
+      this.#alpnProtocol = alpnProtocol ?? null;
+    }
+
+    get peerCertificates() {
+      return this.#peerCertificates;
+    }
+
+    get alpnProtocol() {
+      return this.#alpnProtocol;
+    }
+  }
+
   async function connectTls({
     port,
     hostname = "127.0.0.1",
     transport = "tcp",
     certFile = undefined,
+    caCerts = undefined,
+    certChain = undefined,
+    privateKey = undefined,
+    alpnProtocols = undefined,
   }) {
     const res = await opConnectTls({
       port,
       hostname,
       transport,
       certFile,
+      caCerts,
+      certChain,
+      privateKey,
+      alpnProtocols,
     });
-    return new Conn(res.rid, res.remoteAddr, res.localAddr);
+    return new TlsConn(
+      res.rid,
+      res.remoteAddr,
+      res.localAddr,
+      res.peerCertificates,
+      res.alpnProtocol,
+    );
   }
 
   class TLSListener extends Listener {
     async accept() {
       const res = await opAcceptTLS(this.rid);
-      return new Conn(res.rid, res.remoteAddr, res.localAddr);
+      return new TlsConn(
+        res.rid,
+        res.remoteAddr,
+        res.localAddr,
+        res.peerCertificates,
+        res.alpnProtocol,
+      );
     }
   }
 
   function listenTls({
     port,
-    certFile,
-    keyFile,
+    cert = undefined,
+    certFile = undefined,
+    key = undefined,
+    keyFile = undefined,
     hostname = "0.0.0.0",
     transport = "tcp",
+    clientCa = undefined,
+    requireClientCert = undefined,
+    alpnProtocols = undefined,
+    sniCertificates = undefined,
   }) {
     const res = opListenTls({
       port,
+      cert,
       certFile,
+      key,
       keyFile,
       hostname,
       transport,
+      clientCa,
+      requireClientCert,
+      alpnProtocols,
+      sniCertificates,
     });
     return new TLSListener(res.rid, res.localAddr);
   }
 
   async function startTls(
     conn,
-    { hostname = "127.0.0.1", certFile } = {},
+    {
+      hostname = "127.0.0.1",
+      certFile,
+      caCerts,
+      certChain,
+      privateKey,
+      alpnProtocols,
+      cert,
+      key,
+      keyFile,
+      server = false,
+    } = {},
   ) {
     const res = await opStartTls({
       rid: conn.rid,
       hostname,
       certFile,
+      caCerts,
+      certChain,
+      privateKey,
+      alpnProtocols,
+      cert,
+      key,
+      keyFile,
+      server,
     });
-    return new Conn(res.rid, res.remoteAddr, res.localAddr);
+    return new TlsConn(
+      res.rid,
+      res.remoteAddr,
+      res.localAddr,
+      res.peerCertificates,
+      res.alpnProtocol,
+    );
   }
 
   window.__bootstrap.tls = {
@@ -79,5 +155,6 @@
     listenTls,
     connectTls,
     TLSListener,
+    TlsConn,
   };
 })(this);
@@ -105,6 +105,9 @@
     emit: __bootstrap.compilerApi.emit,
     openPlugin: __bootstrap.plugins.openPlugin,
     kill: __bootstrap.process.kill,
+    Command: __bootstrap.process.Command,
+    runPty: __bootstrap.process.runPty,
+    PtyProcess: __bootstrap.process.PtyProcess,
     setRaw: __bootstrap.tty.setRaw,
     consoleSize: __bootstrap.tty.consoleSize,
     DiagnosticCategory: __bootstrap.diagnostics.DiagnosticCategory,
@@ -125,6 +128,10 @@
     fstat: __bootstrap.fs.fstat,
     ftruncateSync: __bootstrap.fs.ftruncateSync,
     ftruncate: __bootstrap.fs.ftruncate,
+    flockSync: __bootstrap.fs.flockSync,
+    flock: __bootstrap.fs.flock,
+    funlockSync: __bootstrap.fs.funlockSync,
+    funlock: __bootstrap.fs.funlock,
     umask: __bootstrap.fs.umask,
     futime: __bootstrap.fs.futime,
     futimeSync: __bootstrap.fs.futimeSync,
@@ -336,6 +336,10 @@ delete Object.prototype.__proto__;
   };
 
   let hasBootstrapped = false;
+  // The options `Deno.args`, `Deno.pid`, `Deno.ppid` and `Deno.noColor` are
+  // read from in a runtime which will be snapshotted. They are replaced when
+  // the runtime is restored from the snapshot.
+  let mainRuntimeOptions;
 
   function bootstrapMainRuntime(runtimeOptions) {
     if (hasBootstrapped) {
@@ -346,6 +350,15 @@ delete Object.prototype.__proto__;
     delete globalThis.bootstrap;
     util.log("bootstrapMainRuntime");
     hasBootstrapped = true;
+    if (runtimeOptions.willSnapshot) {
+      // Keep a way to restart the runtime once it is restored.
+      Object.defineProperties(globalThis, {
+        bootstrap: {
+          value: { restoreMainRuntime },
+          configurable: true,
+        },
+      });
+    }
     Object.defineProperties(globalThis, windowOrWorkerGlobalScope);
     Object.defineProperties(globalThis, mainRuntimeGlobalProperties);
     Object.setPrototypeOf(globalThis, Window.prototype);
@@ -371,6 +384,7 @@ delete Object.prototype.__proto__;
       pid,
       ppid,
       unstableFlag,
+      willSnapshot,
     } = runtimeOptions;
 
     if (locationHref != null) {
@@ -388,11 +402,23 @@ delete Object.prototype.__proto__;
       close: core.close,
       ...denoNs,
     };
+    if (willSnapshot) {
+      setMainRuntimeOptions(runtimeOptions);
+      Object.defineProperties(finalDenoNs, {
+        pid: util.getterOnly(() => mainRuntimeOptions.pid),
+        ppid: util.getterOnly(() => mainRuntimeOptions.ppid),
+        noColor: util.getterOnly(() => mainRuntimeOptions.noColor),
+        args: util.getterOnly(() => mainRuntimeOptions.args),
+      });
+    } else {
+      Object.defineProperties(finalDenoNs, {
+        pid: util.readOnly(pid),
+        ppid: util.readOnly(ppid),
+        noColor: util.readOnly(noColor),
+        args: util.readOnly(Object.freeze(args)),
+      });
+    }
     Object.defineProperties(finalDenoNs, {
-      pid: util.readOnly(pid),
-      ppid: util.readOnly(ppid),
-      noColor: util.readOnly(noColor),
-      args: util.readOnly(Object.freeze(args)),
       mainModule: util.getterOnly(opMainModule),
     });
 
@@ -411,6 +437,24 @@ delete Object.prototype.__proto__;
     util.log("args", args);
   }
 
+  function setMainRuntimeOptions({ args, noColor, pid, ppid }) {
+    mainRuntimeOptions = { args: Object.freeze(args), noColor, pid, ppid };
+  }
+
+  // Restarts a main runtime restored from a snapshot taken after
+  // `bootstrapMainRuntime()`.
+  function restoreMainRuntime(runtimeOptions) {
+    delete globalThis.bootstrap;
+    util.log("restoreMainRuntime");
+    // The op ids and the callbacks held by the runtime aren't part of the
+    // snapshot.
+    core.ops();
+    core.setMacrotaskCallback(timers.handleTimerMacrotask);
+    util.setLogDebug(runtimeOptions.debugFlag);
+    setMainRuntimeOptions(runtimeOptions);
+    util.log("args", runtimeOptions.args);
+  }
+
   function bootstrapWorkerRuntime(
     runtimeOptions,
     name,
@@ -20,6 +20,7 @@ pub mod http_util;
 pub mod inspector;
 pub mod js;
 pub mod metrics;
+mod op_crates;
 pub mod ops;
 pub mod permissions;
 pub mod resolve_addr;
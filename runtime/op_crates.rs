@@ -0,0 +1,15 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! Shared by `build.rs` and `MainWorker::for_snapshot()`, which both execute
+//! the runtime JavaScript in a runtime that will be snapshotted.
+
+use deno_core::JsRuntime;
+
+/// Execute the JavaScript of the op crates the runtime JavaScript builds on.
+pub fn init(js_runtime: &mut JsRuntime) {
+  deno_web::init(js_runtime);
+  deno_fetch::init(js_runtime);
+  deno_websocket::init(js_runtime);
+  deno_crypto::init(js_runtime);
+  deno_webgpu::init(js_runtime);
+}
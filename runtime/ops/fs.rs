@@ -1,5 +1,6 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 // Some deserializer fields are only used on Unix and Windows build fails without it
+use super::io::MemoryFileResource;
 use super::io::StdFileResource;
 use crate::fs_util::canonicalize_path;
 use crate::permissions::Permissions;
@@ -18,12 +19,14 @@ use deno_crypto::rand::thread_rng;
 use deno_crypto::rand::Rng;
 use serde::Deserialize;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::convert::From;
 use std::env::{current_dir, set_current_dir, temp_dir};
 use std::io;
-use std::io::{Seek, SeekFrom};
-use std::path::{Path, PathBuf};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
 use std::rc::Rc;
+use std::time::Duration;
 use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
 use tokio::io::AsyncSeekExt;
@@ -91,6 +94,11 @@ pub fn init(rt: &mut deno_core::JsRuntime) {
   super::reg_json_sync(rt, "op_ftruncate_sync", op_ftruncate_sync);
   super::reg_json_async(rt, "op_ftruncate_async", op_ftruncate_async);
 
+  super::reg_json_sync(rt, "op_flock_sync", op_flock_sync);
+  super::reg_json_async(rt, "op_flock_async", op_flock_async);
+  super::reg_json_sync(rt, "op_funlock_sync", op_funlock_sync);
+  super::reg_json_async(rt, "op_funlock_async", op_funlock_async);
+
   super::reg_json_sync(rt, "op_truncate_sync", op_truncate_sync);
   super::reg_json_async(rt, "op_truncate_async", op_truncate_async);
 
@@ -116,6 +124,83 @@ fn into_string(s: std::ffi::OsString) -> Result<String, AnyError> {
   })
 }
 
+/// The directory below the file system root which the compile root of a
+/// standalone binary is mapped to, so that `/$deno$/data.txt` refers to the
+/// embedded file `data.txt`.
+pub const EMBEDDED_ROOT: &str = "$deno$";
+
+/// Files embedded in a standalone binary. When present in the `OpState`, these
+/// paths can be opened read-only without a read permission.
+#[derive(Clone, Debug, Default)]
+pub struct EmbeddedFiles {
+  /// The file the embedded contents are read from.
+  pub source: PathBuf,
+  /// Maps the path of each embedded file, relative to the compile root and
+  /// separated by `/`, to the offset and length of its contents in `source`.
+  pub files: HashMap<String, (u64, u64)>,
+}
+
+impl EmbeddedFiles {
+  /// Read the contents of an embedded file. Only absolute paths below
+  /// `EMBEDDED_ROOT` are embedded, relative paths always refer to the real
+  /// file system. Returns `None` if the path is not embedded.
+  pub fn read(&self, path: &Path) -> Option<Result<Vec<u8>, AnyError>> {
+    let (offset, len) = *self.files.get(&embedded_key(path)?)?;
+    Some(self.read_range(offset, len))
+  }
+
+  fn read_range(&self, offset: u64, len: u64) -> Result<Vec<u8>, AnyError> {
+    let mut file = std::fs::File::open(&self.source)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut contents = Vec::with_capacity(len as usize);
+    file.take(len).read_to_end(&mut contents)?;
+    Ok(contents)
+  }
+}
+
+/// Convert a path below `EMBEDDED_ROOT` into the key of an embedded file, which
+/// is its path relative to the compile root.
+fn embedded_key(path: &Path) -> Option<String> {
+  let mut components = path.components();
+  if components.next() != Some(Component::RootDir)
+    || components.next() != Some(Component::Normal(EMBEDDED_ROOT.as_ref()))
+  {
+    return None;
+  }
+  let mut segments = Vec::new();
+  for component in components {
+    match component {
+      Component::Normal(segment) => segments.push(segment.to_str()?),
+      Component::CurDir => {}
+      Component::ParentDir => {
+        segments.pop()?;
+      }
+      _ => return None,
+    }
+  }
+  Some(segments.join("/"))
+}
+
+/// Files embedded in a standalone binary are read-only and not backed by a
+/// file descriptor, so only reading, seeking and `Deno.fstat` work on them.
+fn check_not_embedded(
+  state: &OpState,
+  rid: u32,
+  api_name: &str,
+) -> Result<(), AnyError> {
+  if state
+    .resource_table
+    .get::<MemoryFileResource>(rid)
+    .is_some()
+  {
+    return Err(custom_error(
+      "NotSupported",
+      format!("{} is not supported on embedded files", api_name),
+    ));
+  }
+  Ok(())
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct OpenArgs {
@@ -179,11 +264,43 @@ fn open_helper(
   Ok((path, open_options))
 }
 
+/// Opens an embedded file if the path is embedded and the file is only opened
+/// for reading.
+fn open_embedded(
+  state: &mut OpState,
+  args: &Value,
+) -> Result<Option<u32>, AnyError> {
+  let embedded_files = match state.try_borrow::<EmbeddedFiles>() {
+    Some(embedded_files) => embedded_files,
+    None => return Ok(None),
+  };
+  let args: OpenArgs = serde_json::from_value(args.clone())?;
+  let options = args.options;
+  if options.write
+    || options.append
+    || options.create
+    || options.truncate
+    || options.create_new
+  {
+    return Ok(None);
+  }
+  match embedded_files.read(Path::new(&args.path)) {
+    Some(contents) => {
+      let resource = MemoryFileResource::new(contents?);
+      Ok(Some(state.resource_table.add(resource)))
+    }
+    None => Ok(None),
+  }
+}
+
 fn op_open_sync(
   state: &mut OpState,
   args: Value,
   _zero_copy: &mut [ZeroCopyBuf],
 ) -> Result<Value, AnyError> {
+  if let Some(rid) = open_embedded(state, &args)? {
+    return Ok(json!(rid));
+  }
   let (path, open_options) = open_helper(state, args)?;
   let std_file = open_options.open(path)?;
   let tokio_file = tokio::fs::File::from_std(std_file);
@@ -197,6 +314,9 @@ async fn op_open_async(
   args: Value,
   _zero_copy: BufVec,
 ) -> Result<Value, AnyError> {
+  if let Some(rid) = open_embedded(&mut state.borrow_mut(), &args)? {
+    return Ok(json!(rid));
+  }
   let (path, open_options) = open_helper(&mut state.borrow_mut(), args)?;
   let tokio_file = tokio::fs::OpenOptions::from(open_options)
     .open(path)
@@ -238,6 +358,10 @@ fn op_seek_sync(
   _zero_copy: &mut [ZeroCopyBuf],
 ) -> Result<Value, AnyError> {
   let (rid, seek_from) = seek_helper(args)?;
+  if let Some(memory_file) = state.resource_table.get::<MemoryFileResource>(rid)
+  {
+    return Ok(json!(memory_file.seek(seek_from)?));
+  }
   let pos = StdFileResource::with(state, rid, |r| match r {
     Ok(std_file) => std_file.seek(seek_from).map_err(AnyError::from),
     Err(_) => Err(type_error(
@@ -254,6 +378,12 @@ async fn op_seek_async(
 ) -> Result<Value, AnyError> {
   let (rid, seek_from) = seek_helper(args)?;
 
+  let maybe_memory_file =
+    state.borrow().resource_table.get::<MemoryFileResource>(rid);
+  if let Some(memory_file) = maybe_memory_file {
+    return Ok(json!(memory_file.seek(seek_from)?));
+  }
+
   let resource = state
     .borrow_mut()
     .resource_table
@@ -285,6 +415,7 @@ fn op_fdatasync_sync(
 ) -> Result<Value, AnyError> {
   let args: FdatasyncArgs = serde_json::from_value(args)?;
   let rid = args.rid as u32;
+  check_not_embedded(state, rid, "Deno.fdatasync")?;
   StdFileResource::with(state, rid, |r| match r {
     Ok(std_file) => std_file.sync_data().map_err(AnyError::from),
     Err(_) => Err(type_error("cannot sync this type of resource".to_string())),
@@ -299,6 +430,7 @@ async fn op_fdatasync_async(
 ) -> Result<Value, AnyError> {
   let args: FdatasyncArgs = serde_json::from_value(args)?;
   let rid = args.rid as u32;
+  check_not_embedded(&state.borrow(), rid, "Deno.fdatasync")?;
 
   let resource = state
     .borrow_mut()
@@ -331,6 +463,7 @@ fn op_fsync_sync(
 ) -> Result<Value, AnyError> {
   let args: FsyncArgs = serde_json::from_value(args)?;
   let rid = args.rid as u32;
+  check_not_embedded(state, rid, "Deno.fsync")?;
   StdFileResource::with(state, rid, |r| match r {
     Ok(std_file) => std_file.sync_all().map_err(AnyError::from),
     Err(_) => Err(type_error("cannot sync this type of resource".to_string())),
@@ -345,6 +478,7 @@ async fn op_fsync_async(
 ) -> Result<Value, AnyError> {
   let args: FsyncArgs = serde_json::from_value(args)?;
   let rid = args.rid as u32;
+  check_not_embedded(&state.borrow(), rid, "Deno.fsync")?;
 
   let resource = state
     .borrow_mut()
@@ -378,6 +512,10 @@ fn op_fstat_sync(
   super::check_unstable(state, "Deno.fstat");
   let args: FstatArgs = serde_json::from_value(args)?;
   let rid = args.rid as u32;
+  if let Some(memory_file) = state.resource_table.get::<MemoryFileResource>(rid)
+  {
+    return Ok(get_embedded_stat_json(memory_file.size()));
+  }
   let metadata = StdFileResource::with(state, rid, |r| match r {
     Ok(std_file) => std_file.metadata().map_err(AnyError::from),
     Err(_) => Err(type_error("cannot stat this type of resource".to_string())),
@@ -395,6 +533,12 @@ async fn op_fstat_async(
   let args: FstatArgs = serde_json::from_value(args)?;
   let rid = args.rid as u32;
 
+  let maybe_memory_file =
+    state.borrow().resource_table.get::<MemoryFileResource>(rid);
+  if let Some(memory_file) = maybe_memory_file {
+    return Ok(get_embedded_stat_json(memory_file.size()));
+  }
+
   let resource = state
     .borrow_mut()
     .resource_table
@@ -893,6 +1037,29 @@ fn get_stat_json(metadata: std::fs::Metadata) -> Value {
   json_val
 }
 
+/// The stat of a file embedded in a standalone binary, of which only the size
+/// is known.
+fn get_embedded_stat_json(size: u64) -> Value {
+  json!({
+    "isFile": true,
+    "isDirectory": false,
+    "isSymlink": false,
+    "size": size,
+    "mtime": Value::Null,
+    "atime": Value::Null,
+    "birthtime": Value::Null,
+    "dev": 0,
+    "ino": 0,
+    "mode": 0,
+    "nlink": 0,
+    "uid": 0,
+    "gid": 0,
+    "rdev": 0,
+    "blksize": 0,
+    "blocks": 0,
+  })
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct StatArgs {
@@ -1360,6 +1527,7 @@ fn op_ftruncate_sync(
   let args: FtruncateArgs = serde_json::from_value(args)?;
   let rid = args.rid as u32;
   let len = args.len as u64;
+  check_not_embedded(state, rid, "Deno.ftruncate")?;
   StdFileResource::with(state, rid, |r| match r {
     Ok(std_file) => std_file.set_len(len).map_err(AnyError::from),
     Err(_) => Err(type_error("cannot truncate this type of resource")),
@@ -1376,6 +1544,7 @@ async fn op_ftruncate_async(
   let args: FtruncateArgs = serde_json::from_value(args)?;
   let rid = args.rid as u32;
   let len = args.len as u64;
+  check_not_embedded(&state.borrow(), rid, "Deno.ftruncate")?;
 
   let resource = state
     .borrow_mut()
@@ -1395,6 +1564,221 @@ async fn op_ftruncate_async(
   Ok(json!({}))
 }
 
+/// How long an asynchronous `Deno.flock` waits before trying again to take a
+/// lock which is held elsewhere.
+const FLOCK_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Locks the file. When `blocking` is false, returns `Ok(false)` instead of
+/// waiting if the lock is held elsewhere.
+#[cfg(unix)]
+fn flock<F: std::os::unix::io::AsRawFd>(
+  file: &F,
+  exclusive: bool,
+  blocking: bool,
+) -> Result<bool, AnyError> {
+  let mut operation = if exclusive {
+    libc::LOCK_EX
+  } else {
+    libc::LOCK_SH
+  };
+  if !blocking {
+    operation |= libc::LOCK_NB;
+  }
+  if unsafe { libc::flock(file.as_raw_fd(), operation) } != 0 {
+    let err = io::Error::last_os_error();
+    if !blocking && err.kind() == io::ErrorKind::WouldBlock {
+      return Ok(false);
+    }
+    return Err(err.into());
+  }
+  Ok(true)
+}
+
+#[cfg(unix)]
+fn funlock<F: std::os::unix::io::AsRawFd>(file: &F) -> Result<(), AnyError> {
+  if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_UN) } != 0 {
+    return Err(io::Error::last_os_error().into());
+  }
+  Ok(())
+}
+
+/// Locks the file. When `blocking` is false, returns `Ok(false)` instead of
+/// waiting if the lock is held elsewhere.
+#[cfg(windows)]
+fn flock<F: std::os::windows::io::AsRawHandle>(
+  file: &F,
+  exclusive: bool,
+  blocking: bool,
+) -> Result<bool, AnyError> {
+  use winapi::shared::minwindef::DWORD;
+  use winapi::shared::winerror::ERROR_LOCK_VIOLATION;
+  use winapi::um::fileapi::LockFileEx;
+  use winapi::um::minwinbase::{
+    LOCKFILE_EXCLUSIVE_LOCK, LOCKFILE_FAIL_IMMEDIATELY, OVERLAPPED,
+  };
+
+  let mut flags = if exclusive {
+    LOCKFILE_EXCLUSIVE_LOCK
+  } else {
+    0
+  };
+  if !blocking {
+    flags |= LOCKFILE_FAIL_IMMEDIATELY;
+  }
+  // Lock the whole file, no matter how large it grows.
+  let ret = unsafe {
+    let mut overlapped: OVERLAPPED = std::mem::zeroed();
+    LockFileEx(
+      file.as_raw_handle(),
+      flags,
+      0,
+      DWORD::MAX,
+      DWORD::MAX,
+      &mut overlapped,
+    )
+  };
+  if ret == 0 {
+    let err = io::Error::last_os_error();
+    if !blocking && err.raw_os_error() == Some(ERROR_LOCK_VIOLATION as i32) {
+      return Ok(false);
+    }
+    return Err(err.into());
+  }
+  Ok(true)
+}
+
+#[cfg(windows)]
+fn funlock<F: std::os::windows::io::AsRawHandle>(
+  file: &F,
+) -> Result<(), AnyError> {
+  use winapi::shared::minwindef::DWORD;
+  use winapi::um::fileapi::UnlockFileEx;
+  use winapi::um::minwinbase::OVERLAPPED;
+
+  let ret = unsafe {
+    let mut overlapped: OVERLAPPED = std::mem::zeroed();
+    UnlockFileEx(
+      file.as_raw_handle(),
+      0,
+      DWORD::MAX,
+      DWORD::MAX,
+      &mut overlapped,
+    )
+  };
+  if ret == 0 {
+    return Err(io::Error::last_os_error().into());
+  }
+  Ok(())
+}
+
+/// Calls `f` with the file of the resource itself. Locks must be taken on the
+/// resource's own handle: on Windows they belong to the handle and would be
+/// released as soon as a cloned handle is closed.
+async fn with_tokio_file<F, R>(
+  state: &Rc<RefCell<OpState>>,
+  rid: u32,
+  f: F,
+) -> Result<R, AnyError>
+where
+  F: FnOnce(&tokio::fs::File) -> Result<R, AnyError>,
+{
+  let resource = state
+    .borrow_mut()
+    .resource_table
+    .get::<StdFileResource>(rid)
+    .ok_or_else(bad_resource_id)?;
+
+  if resource.fs_file.is_none() {
+    return Err(bad_resource_id());
+  }
+
+  let fs_file = RcRef::map(&resource, |r| r.fs_file.as_ref().unwrap())
+    .borrow_mut()
+    .await;
+
+  let tokio_file = (*fs_file).0.as_ref().ok_or_else(bad_resource_id)?;
+  f(tokio_file)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FlockArgs {
+  rid: u32,
+  exclusive: bool,
+}
+
+fn op_flock_sync(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  super::check_unstable(state, "Deno.flock");
+  let args: FlockArgs = serde_json::from_value(args)?;
+  check_not_embedded(state, args.rid, "Deno.flock")?;
+  StdFileResource::with(state, args.rid, |r| match r {
+    Ok(std_file) => flock(std_file, args.exclusive, true),
+    Err(_) => Err(type_error("cannot lock this type of resource")),
+  })?;
+  Ok(json!({}))
+}
+
+async fn op_flock_async(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  _zero_copy: BufVec,
+) -> Result<Value, AnyError> {
+  super::check_unstable2(&state, "Deno.flock");
+  let args: FlockArgs = serde_json::from_value(args)?;
+  check_not_embedded(&state.borrow(), args.rid, "Deno.flock")?;
+
+  // Waiting for the lock on a blocking thread would need a cloned handle, so
+  // instead poll for it without blocking the event loop.
+  loop {
+    let locked = with_tokio_file(&state, args.rid, |file| {
+      flock(file, args.exclusive, false)
+    })
+    .await?;
+    if locked {
+      return Ok(json!({}));
+    }
+    tokio::time::sleep(FLOCK_RETRY_INTERVAL).await;
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FunlockArgs {
+  rid: u32,
+}
+
+fn op_funlock_sync(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  super::check_unstable(state, "Deno.funlock");
+  let args: FunlockArgs = serde_json::from_value(args)?;
+  check_not_embedded(state, args.rid, "Deno.funlock")?;
+  StdFileResource::with(state, args.rid, |r| match r {
+    Ok(std_file) => funlock(std_file),
+    Err(_) => Err(type_error("cannot unlock this type of resource")),
+  })?;
+  Ok(json!({}))
+}
+
+async fn op_funlock_async(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  _zero_copy: BufVec,
+) -> Result<Value, AnyError> {
+  super::check_unstable2(&state, "Deno.funlock");
+  let args: FunlockArgs = serde_json::from_value(args)?;
+  check_not_embedded(&state.borrow(), args.rid, "Deno.funlock")?;
+  // Unlocking never waits, so it is done right away on the resource's handle.
+  with_tokio_file(&state, args.rid, funlock).await?;
+  Ok(json!({}))
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct TruncateArgs {
@@ -1642,6 +2026,7 @@ fn op_futime_sync(
   let rid = args.rid as u32;
   let atime = filetime::FileTime::from_unix_time(args.atime.0, args.atime.1);
   let mtime = filetime::FileTime::from_unix_time(args.mtime.0, args.mtime.1);
+  check_not_embedded(state, rid, "Deno.futime")?;
 
   StdFileResource::with(state, rid, |r| match r {
     Ok(std_file) => {
@@ -1666,6 +2051,7 @@ async fn op_futime_async(
   let rid = args.rid as u32;
   let atime = filetime::FileTime::from_unix_time(args.atime.0, args.atime.1);
   let mtime = filetime::FileTime::from_unix_time(args.mtime.0, args.mtime.1);
+  check_not_embedded(&state.borrow(), rid, "Deno.futime")?;
 
   let resource = state
     .borrow_mut()
@@ -3,6 +3,7 @@
 use super::dispatch_minimal::minimal_op;
 use super::dispatch_minimal::MinimalOp;
 use crate::metrics::metrics_op;
+use deno_core::error::custom_error;
 use deno_core::error::resource_unavailable;
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
@@ -26,8 +27,10 @@ use std::borrow::Cow;
 use std::cell::RefCell;
 use std::convert::TryInto;
 use std::io::Read;
+use std::io::Seek;
 use std::io::Write;
 use std::rc::Rc;
+use std::time::Duration;
 use tokio::io::split;
 use tokio::io::AsyncRead;
 use tokio::io::AsyncReadExt;
@@ -40,13 +43,19 @@ use tokio::net::TcpStream;
 use tokio::process;
 use tokio_rustls as tls;
 
+#[cfg(unix)]
+use std::os::unix::io::AsRawFd;
 #[cfg(unix)]
 use std::os::unix::io::FromRawFd;
 #[cfg(unix)]
 use tokio::net::unix;
 
+#[cfg(windows)]
+use std::os::windows::io::AsRawSocket;
 #[cfg(windows)]
 use std::os::windows::io::FromRawHandle;
+#[cfg(windows)]
+use std::os::windows::io::FromRawSocket;
 
 lazy_static! {
   /// Due to portability issues on Windows handle to stdout is created from raw
@@ -349,6 +358,77 @@ impl Resource for TcpStreamResource {
   }
 }
 
+impl TcpStreamResource {
+  /// Calls `f` with the socket of the stream. Socket options can only be set
+  /// while no write is pending, as the write half has to be borrowed.
+  fn with_socket<R>(
+    self: &Rc<Self>,
+    f: impl FnOnce(&TcpStream) -> std::io::Result<R>,
+  ) -> Result<R, AnyError> {
+    let wr = RcRef::map(self, |r| &r.wr)
+      .try_borrow()
+      .ok_or_else(|| custom_error("Busy", "TCP stream is currently in use"))?;
+    let stream: &TcpStream = (&*wr).as_ref();
+    Ok(f(stream)?)
+  }
+
+  pub fn set_nodelay(self: &Rc<Self>, nodelay: bool) -> Result<(), AnyError> {
+    self.with_socket(|stream| stream.set_nodelay(nodelay))
+  }
+
+  pub fn set_keepalive(
+    self: &Rc<Self>,
+    keepalive: Option<Duration>,
+  ) -> Result<(), AnyError> {
+    self.with_socket(|stream| {
+      with_socket2(stream, |socket| socket.set_keepalive(keepalive))
+    })
+  }
+
+  pub fn set_send_buffer_size(
+    self: &Rc<Self>,
+    size: usize,
+  ) -> Result<(), AnyError> {
+    self.with_socket(|stream| {
+      with_socket2(stream, |socket| socket.set_send_buffer_size(size))
+    })
+  }
+
+  pub fn set_recv_buffer_size(
+    self: &Rc<Self>,
+    size: usize,
+  ) -> Result<(), AnyError> {
+    self.with_socket(|stream| {
+      with_socket2(stream, |socket| socket.set_recv_buffer_size(size))
+    })
+  }
+}
+
+/// Calls `f` with a `socket2::Socket` borrowing the socket of the stream, for
+/// the options which tokio doesn't expose.
+#[cfg(unix)]
+pub(crate) fn with_socket2<S: AsRawFd, R>(
+  stream: &S,
+  f: impl FnOnce(&socket2::Socket) -> std::io::Result<R>,
+) -> std::io::Result<R> {
+  let socket = unsafe { socket2::Socket::from_raw_fd(stream.as_raw_fd()) };
+  // The socket is still owned by the stream, so it must not be closed here.
+  let socket = std::mem::ManuallyDrop::new(socket);
+  f(&socket)
+}
+
+#[cfg(windows)]
+pub(crate) fn with_socket2<S: AsRawSocket, R>(
+  stream: &S,
+  f: impl FnOnce(&socket2::Socket) -> std::io::Result<R>,
+) -> std::io::Result<R> {
+  let socket =
+    unsafe { socket2::Socket::from_raw_socket(stream.as_raw_socket()) };
+  // The socket is still owned by the stream, so it must not be closed here.
+  let socket = std::mem::ManuallyDrop::new(socket);
+  f(&socket)
+}
+
 pub type TlsClientStreamResource =
   FullDuplexSplitResource<tls::client::TlsStream<TcpStream>>;
 
@@ -414,6 +494,8 @@ pub struct StdFileResource {
     Option<AsyncRefCell<(Option<tokio::fs::File>, Option<FileMetadata>)>>,
   cancel: CancelHandle,
   name: String,
+  /// Whether the file is the master side of a pseudo-terminal.
+  is_pty: bool,
 }
 
 impl StdFileResource {
@@ -439,12 +521,32 @@ impl StdFileResource {
     }
   }
 
+  /// The master side of a pseudo-terminal.
+  #[cfg(unix)]
+  pub fn pty(fs_file: tokio::fs::File) -> Self {
+    Self {
+      fs_file: Some(AsyncRefCell::new((
+        Some(fs_file),
+        Some(FileMetadata::default()),
+      ))),
+      name: "pty".to_string(),
+      is_pty: true,
+      ..Default::default()
+    }
+  }
+
   async fn read(self: &Rc<Self>, buf: &mut [u8]) -> Result<usize, AnyError> {
     if self.fs_file.is_some() {
       let mut fs_file = RcRef::map(&*self, |r| r.fs_file.as_ref().unwrap())
         .borrow_mut()
         .await;
-      let nwritten = fs_file.0.as_mut().unwrap().read(buf).await?;
+      let nwritten = match fs_file.0.as_mut().unwrap().read(buf).await {
+        // Reading the master side of a pseudo-terminal fails with EIO once
+        // the slave side has been closed, which is its end of file.
+        #[cfg(unix)]
+        Err(e) if self.is_pty && e.raw_os_error() == Some(libc::EIO) => 0,
+        result => result?,
+      };
       return Ok(nwritten);
     } else {
       Err(resource_unavailable())
@@ -526,6 +628,39 @@ impl Resource for StdFileResource {
   }
 }
 
+/// A read-only file whose contents are held in memory, such as a file embedded
+/// in a standalone binary.
+#[derive(Debug)]
+pub struct MemoryFileResource {
+  cursor: RefCell<std::io::Cursor<Vec<u8>>>,
+}
+
+impl MemoryFileResource {
+  pub fn new(contents: Vec<u8>) -> Self {
+    Self {
+      cursor: RefCell::new(std::io::Cursor::new(contents)),
+    }
+  }
+
+  pub fn read(&self, buf: &mut [u8]) -> Result<usize, AnyError> {
+    Ok(self.cursor.borrow_mut().read(buf)?)
+  }
+
+  pub fn seek(&self, pos: std::io::SeekFrom) -> Result<u64, AnyError> {
+    Ok(self.cursor.borrow_mut().seek(pos)?)
+  }
+
+  pub fn size(&self) -> u64 {
+    self.cursor.borrow().get_ref().len() as u64
+  }
+}
+
+impl Resource for MemoryFileResource {
+  fn name(&self) -> Cow<str> {
+    "memoryFile".into()
+  }
+}
+
 pub fn op_read(
   state: Rc<RefCell<OpState>>,
   is_sync: bool,
@@ -552,6 +687,11 @@ fn op_read_sync(
   mut buf: ZeroCopyBuf,
 ) -> Result<i32, AnyError> {
   let rid = rid.try_into().map_err(|_| bad_resource_id())?;
+  let maybe_memory_file =
+    state.borrow().resource_table.get::<MemoryFileResource>(rid);
+  if let Some(memory_file) = maybe_memory_file {
+    return memory_file.read(&mut buf).map(|n| n as i32);
+  }
   StdFileResource::with(&mut state.borrow_mut(), rid, move |r| match r {
     Ok(std_file) => std_file
       .read(&mut buf)
@@ -586,6 +726,8 @@ async fn op_read_async(
     s.read(&mut buf).await?
   } else if let Some(s) = resource.downcast_rc::<StdFileResource>() {
     s.read(&mut buf).await?
+  } else if let Some(s) = resource.downcast_rc::<MemoryFileResource>() {
+    s.read(&mut buf)?
   } else {
     return Err(not_supported());
   };
@@ -1,10 +1,12 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
+use crate::ops::io::with_socket2;
 use crate::ops::io::TcpStreamResource;
 use crate::permissions::Permissions;
 use crate::resolve_addr::resolve_addr;
 use crate::resolve_addr::resolve_addr_sync;
 use deno_core::error::bad_resource;
+use deno_core::error::bad_resource_id;
 use deno_core::error::custom_error;
 use deno_core::error::generic_error;
 use deno_core::error::type_error;
@@ -24,8 +26,11 @@ use serde::Deserialize;
 use serde::Serialize;
 use std::borrow::Cow;
 use std::cell::RefCell;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
 use std::net::SocketAddr;
 use std::rc::Rc;
+use std::time::Duration;
 use tokio::net::TcpListener;
 use tokio::net::TcpStream;
 use tokio::net::UdpSocket;
@@ -37,6 +42,9 @@ use trust_dns_resolver::config::ResolverOpts;
 use trust_dns_resolver::system_conf;
 use trust_dns_resolver::AsyncResolver;
 
+#[cfg(not(unix))]
+use deno_core::error::not_supported;
+
 #[cfg(unix)]
 use super::net_unix;
 #[cfg(unix)]
@@ -51,6 +59,18 @@ pub fn init(rt: &mut deno_core::JsRuntime) {
   super::reg_json_async(rt, "op_datagram_receive", op_datagram_receive);
   super::reg_json_async(rt, "op_datagram_send", op_datagram_send);
   super::reg_json_async(rt, "op_dns_resolve", op_dns_resolve);
+  super::reg_json_sync(rt, "op_set_nodelay", op_set_nodelay);
+  super::reg_json_sync(rt, "op_set_keepalive", op_set_keepalive);
+  super::reg_json_sync(rt, "op_set_buffer_size", op_set_buffer_size);
+  super::reg_json_sync(rt, "op_join_multicast", op_join_multicast);
+  super::reg_json_sync(rt, "op_leave_multicast", op_leave_multicast);
+  super::reg_json_sync(
+    rt,
+    "op_set_multicast_loopback",
+    op_set_multicast_loopback,
+  );
+  super::reg_json_sync(rt, "op_set_multicast_ttl", op_set_multicast_ttl);
+  super::reg_json_sync(rt, "op_set_broadcast", op_set_broadcast);
 }
 
 #[derive(Deserialize)]
@@ -241,6 +261,7 @@ async fn op_datagram_send(
       let socket = RcRef::map(&resource, |r| &r.socket)
         .try_borrow_mut()
         .ok_or_else(|| custom_error("Busy", "Socket already in use"))?;
+      net_unix::check_abstract_path(address_path)?;
       let byte_length = socket.send_to(&zero_copy, address_path).await?;
       Ok(json!(byte_length))
     }
@@ -333,6 +354,259 @@ async fn op_connect(
   }
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetNoDelayArgs {
+  rid: u32,
+  no_delay: bool,
+}
+
+fn op_set_nodelay(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  super::check_unstable(state, "Deno.Conn.setNoDelay");
+  let args: SetNoDelayArgs = serde_json::from_value(args)?;
+  let resource = state
+    .resource_table
+    .get::<TcpStreamResource>(args.rid)
+    .ok_or_else(bad_resource_id)?;
+  resource.set_nodelay(args.no_delay)?;
+  Ok(json!({}))
+}
+
+#[derive(Deserialize)]
+struct SetKeepAliveArgs {
+  rid: u32,
+  /// The idle time in milliseconds before keep-alive probes are sent, or
+  /// `None` to disable keep-alive.
+  interval: Option<u64>,
+}
+
+fn op_set_keepalive(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  super::check_unstable(state, "Deno.Conn.setKeepAlive");
+  let args: SetKeepAliveArgs = serde_json::from_value(args)?;
+  let resource = state
+    .resource_table
+    .get::<TcpStreamResource>(args.rid)
+    .ok_or_else(bad_resource_id)?;
+  resource.set_keepalive(args.interval.map(Duration::from_millis))?;
+  Ok(json!({}))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetBufferSizeArgs {
+  rid: u32,
+  send_buffer_size: Option<usize>,
+  recv_buffer_size: Option<usize>,
+}
+
+fn op_set_buffer_size(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  super::check_unstable(state, "Deno.Conn.setBufferSize");
+  let args: SetBufferSizeArgs = serde_json::from_value(args)?;
+  let resource = state
+    .resource_table
+    .get::<TcpStreamResource>(args.rid)
+    .ok_or_else(bad_resource_id)?;
+  if let Some(size) = args.send_buffer_size {
+    resource.set_send_buffer_size(size)?;
+  }
+  if let Some(size) = args.recv_buffer_size {
+    resource.set_recv_buffer_size(size)?;
+  }
+  Ok(json!({}))
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MulticastInterface {
+  /// The address of the interface, for IPv4 groups.
+  V4(String),
+  /// The index of the interface, for IPv6 groups.
+  V6(u32),
+}
+
+#[derive(Deserialize)]
+struct MulticastArgs {
+  rid: u32,
+  address: String,
+  interface: Option<MulticastInterface>,
+}
+
+/// Joins or leaves the multicast group given by `args`, depending on `join`.
+fn update_multicast_membership(
+  state: &mut OpState,
+  args: MulticastArgs,
+  join: bool,
+) -> Result<(), AnyError> {
+  let address: IpAddr = args
+    .address
+    .parse()
+    .map_err(|_| type_error(format!("Invalid address: {}", args.address)))?;
+  if !address.is_multicast() {
+    return Err(type_error(format!(
+      "Not a multicast address: {}",
+      args.address
+    )));
+  }
+  state
+    .borrow::<Permissions>()
+    .check_net(&(&args.address, None))?;
+  let resource = state
+    .resource_table
+    .get::<UdpSocketResource>(args.rid)
+    .ok_or_else(bad_resource_id)?;
+  resource.with_socket(|socket| match (address, args.interface) {
+    (IpAddr::V4(address), interface) => {
+      let interface = match interface {
+        None => Ipv4Addr::UNSPECIFIED,
+        Some(MulticastInterface::V4(interface)) => {
+          interface.parse().map_err(|_| {
+            type_error(format!("Invalid interface address: {}", interface))
+          })?
+        }
+        Some(MulticastInterface::V6(_)) => {
+          return Err(type_error(
+            "The interface of an IPv4 group has to be an IPv4 address",
+          ))
+        }
+      };
+      if join {
+        socket.join_multicast_v4(address, interface)?;
+      } else {
+        socket.leave_multicast_v4(address, interface)?;
+      }
+      Ok(())
+    }
+    (IpAddr::V6(address), interface) => {
+      let interface = match interface {
+        None => 0,
+        Some(MulticastInterface::V6(index)) => index,
+        Some(MulticastInterface::V4(_)) => {
+          return Err(type_error(
+            "The interface of an IPv6 group has to be an interface index",
+          ))
+        }
+      };
+      if join {
+        socket.join_multicast_v6(&address, interface)?;
+      } else {
+        socket.leave_multicast_v6(&address, interface)?;
+      }
+      Ok(())
+    }
+  })
+}
+
+fn op_join_multicast(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  super::check_unstable(state, "Deno.DatagramConn.joinMulticast");
+  let args: MulticastArgs = serde_json::from_value(args)?;
+  update_multicast_membership(state, args, true)?;
+  Ok(json!({}))
+}
+
+fn op_leave_multicast(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  super::check_unstable(state, "Deno.DatagramConn.leaveMulticast");
+  let args: MulticastArgs = serde_json::from_value(args)?;
+  update_multicast_membership(state, args, false)?;
+  Ok(json!({}))
+}
+
+#[derive(Deserialize)]
+struct SetMulticastLoopbackArgs {
+  rid: u32,
+  loopback: bool,
+}
+
+fn op_set_multicast_loopback(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  super::check_unstable(state, "Deno.DatagramConn.setMulticastLoopback");
+  let args: SetMulticastLoopbackArgs = serde_json::from_value(args)?;
+  let resource = state
+    .resource_table
+    .get::<UdpSocketResource>(args.rid)
+    .ok_or_else(bad_resource_id)?;
+  resource.with_socket(|socket| {
+    if socket.local_addr()?.is_ipv4() {
+      socket.set_multicast_loop_v4(args.loopback)?;
+    } else {
+      socket.set_multicast_loop_v6(args.loopback)?;
+    }
+    Ok(())
+  })?;
+  Ok(json!({}))
+}
+
+#[derive(Deserialize)]
+struct SetMulticastTtlArgs {
+  rid: u32,
+  ttl: u32,
+}
+
+fn op_set_multicast_ttl(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  super::check_unstable(state, "Deno.DatagramConn.setMulticastTTL");
+  let args: SetMulticastTtlArgs = serde_json::from_value(args)?;
+  let resource = state
+    .resource_table
+    .get::<UdpSocketResource>(args.rid)
+    .ok_or_else(bad_resource_id)?;
+  resource.with_socket(|socket| {
+    if socket.local_addr()?.is_ipv4() {
+      socket.set_multicast_ttl_v4(args.ttl)?;
+    } else {
+      with_socket2(socket, |socket| socket.set_multicast_hops_v6(args.ttl))?;
+    }
+    Ok(())
+  })?;
+  Ok(json!({}))
+}
+
+#[derive(Deserialize)]
+struct SetBroadcastArgs {
+  rid: u32,
+  broadcast: bool,
+}
+
+fn op_set_broadcast(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  super::check_unstable(state, "Deno.DatagramConn.setBroadcast");
+  let args: SetBroadcastArgs = serde_json::from_value(args)?;
+  let resource = state
+    .resource_table
+    .get::<UdpSocketResource>(args.rid)
+    .ok_or_else(bad_resource_id)?;
+  resource.with_socket(|socket| Ok(socket.set_broadcast(args.broadcast)?))?;
+  Ok(json!({}))
+}
+
 struct TcpListenerResource {
   listener: AsyncRefCell<TcpListener>,
   cancel: CancelHandle,
@@ -363,10 +637,26 @@ impl Resource for UdpSocketResource {
   }
 }
 
+impl UdpSocketResource {
+  /// Calls `f` with the socket. Receives and sends only borrow the socket
+  /// immutably, so this succeeds while they are pending.
+  fn with_socket<R>(
+    self: &Rc<Self>,
+    f: impl FnOnce(&UdpSocket) -> Result<R, AnyError>,
+  ) -> Result<R, AnyError> {
+    let socket = RcRef::map(self, |r| &r.socket)
+      .try_borrow()
+      .ok_or_else(|| custom_error("Busy", "Socket is currently in use"))?;
+    f(&*socket)
+  }
+}
+
 #[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct IpListenArgs {
   hostname: String,
   port: u16,
+  reuse_port: Option<bool>,
 }
 
 #[derive(Deserialize)]
@@ -384,11 +674,45 @@ struct ListenArgs {
   transport_args: ArgsEnum,
 }
 
+/// Binds a socket with `SO_REUSEPORT` set, so several processes can listen on
+/// the same address.
+#[cfg(unix)]
+fn bind_reuse_port(
+  addr: SocketAddr,
+  socket_type: socket2::Type,
+) -> Result<socket2::Socket, AnyError> {
+  let domain = if addr.is_ipv4() {
+    socket2::Domain::ipv4()
+  } else {
+    socket2::Domain::ipv6()
+  };
+  let socket = socket2::Socket::new(domain, socket_type, None)?;
+  socket.set_reuse_address(true)?;
+  socket.set_reuse_port(true)?;
+  socket.bind(&addr.into())?;
+  Ok(socket)
+}
+
+#[cfg(not(unix))]
+fn bind_reuse_port(
+  _addr: SocketAddr,
+  _socket_type: socket2::Type,
+) -> Result<socket2::Socket, AnyError> {
+  Err(not_supported())
+}
+
 fn listen_tcp(
   state: &mut OpState,
   addr: SocketAddr,
+  reuse_port: bool,
 ) -> Result<(u32, SocketAddr), AnyError> {
-  let std_listener = std::net::TcpListener::bind(&addr)?;
+  let std_listener = if reuse_port {
+    let socket = bind_reuse_port(addr, socket2::Type::stream())?;
+    socket.listen(128)?;
+    socket.into_tcp_listener()
+  } else {
+    std::net::TcpListener::bind(&addr)?
+  };
   std_listener.set_nonblocking(true)?;
   let listener = TcpListener::from_std(std_listener)?;
   let local_addr = listener.local_addr()?;
@@ -404,8 +728,13 @@ fn listen_tcp(
 fn listen_udp(
   state: &mut OpState,
   addr: SocketAddr,
+  reuse_port: bool,
 ) -> Result<(u32, SocketAddr), AnyError> {
-  let std_socket = std::net::UdpSocket::bind(&addr)?;
+  let std_socket = if reuse_port {
+    bind_reuse_port(addr, socket2::Type::dgram())?.into_udp_socket()
+  } else {
+    std::net::UdpSocket::bind(&addr)?
+  };
   std_socket.set_nonblocking(true)?;
   let socket = UdpSocket::from_std(std_socket)?;
   let local_addr = socket.local_addr()?;
@@ -435,13 +764,17 @@ fn op_listen(
         }
         permissions.check_net(&(&args.hostname, Some(args.port)))?;
       }
+      let reuse_port = args.reuse_port.unwrap_or(false);
+      if reuse_port {
+        super::check_unstable(state, "Deno.listen.reusePort");
+      }
       let addr = resolve_addr_sync(&args.hostname, args.port)?
         .next()
         .ok_or_else(|| generic_error("No resolved address found"))?;
       let (rid, local_addr) = if transport == "tcp" {
-        listen_tcp(state, addr)?
+        listen_tcp(state, addr, reuse_port)?
       } else {
-        listen_udp(state, addr)?
+        listen_udp(state, addr, reuse_port)?
       };
       debug!(
         "New listener {} {}:{}",
@@ -479,15 +812,14 @@ fn op_listen(
       } else {
         net_unix::listen_unix_packet(state, &address_path)?
       };
-      debug!(
-        "New listener {} {}",
-        rid,
-        local_addr.as_pathname().unwrap().display(),
-      );
+      debug!("New listener {} {}", rid, address_path.display());
+      // Sockets in the abstract namespace have no path name, so report the
+      // address they were bound to instead.
+      let local_path = local_addr.as_pathname().unwrap_or(address_path);
       Ok(json!({
       "rid": rid,
       "localAddr": {
-        "path": local_addr.as_pathname(),
+        "path": local_path,
         "transport": transport,
       },
       }))
@@ -19,6 +19,7 @@ use serde::Deserialize;
 use std::borrow::Cow;
 use std::cell::RefCell;
 use std::fs::remove_file;
+use std::os::unix::ffi::OsStrExt;
 use std::path::Path;
 use std::rc::Rc;
 use tokio::net::UnixDatagram;
@@ -145,11 +146,30 @@ pub fn listen_unix(
   Ok((rid, local_addr))
 }
 
+/// Returns true if `path` names a socket in the Linux abstract namespace,
+/// i.e. it starts with a null byte and has no file system representation.
+pub fn is_abstract_path(path: &Path) -> bool {
+  path.as_os_str().as_bytes().first() == Some(&0)
+}
+
+/// Rejects abstract socket addresses on platforms that don't have them.
+pub fn check_abstract_path(path: &Path) -> Result<(), AnyError> {
+  if is_abstract_path(path) && !cfg!(target_os = "linux") {
+    return Err(custom_error(
+      "NotSupported",
+      "Abstract socket addresses are only supported on Linux",
+    ));
+  }
+  Ok(())
+}
+
 pub fn listen_unix_packet(
   state: &mut OpState,
   addr: &Path,
 ) -> Result<(u32, tokio::net::unix::SocketAddr), AnyError> {
-  if addr.exists() {
+  if is_abstract_path(addr) {
+    check_abstract_path(addr)?;
+  } else if addr.exists() {
     remove_file(&addr).unwrap();
   }
   let socket = UnixDatagram::bind(&addr)?;
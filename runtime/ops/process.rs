@@ -3,6 +3,7 @@
 use super::io::ChildStderrResource;
 use super::io::ChildStdinResource;
 use super::io::ChildStdoutResource;
+use super::io::ReadOnlyResource;
 use super::io::StdFileResource;
 use crate::permissions::Permissions;
 use deno_core::error::bad_resource_id;
@@ -14,6 +15,7 @@ use deno_core::serde_json::Value;
 use deno_core::AsyncMutFuture;
 use deno_core::AsyncRefCell;
 use deno_core::BufVec;
+use deno_core::CancelTryFuture;
 use deno_core::OpState;
 use deno_core::RcRef;
 use deno_core::Resource;
@@ -21,9 +23,16 @@ use deno_core::ZeroCopyBuf;
 use serde::Deserialize;
 use std::borrow::Cow;
 use std::cell::RefCell;
+use std::process::ExitStatus;
 use std::rc::Rc;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt;
 use tokio::process::Command;
 
+#[cfg(not(unix))]
+use deno_core::error::not_supported;
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
 #[cfg(unix)]
 use std::os::unix::process::ExitStatusExt;
 
@@ -31,6 +40,9 @@ pub fn init(rt: &mut deno_core::JsRuntime) {
   super::reg_json_sync(rt, "op_run", op_run);
   super::reg_json_async(rt, "op_run_status", op_run_status);
   super::reg_json_sync(rt, "op_kill", op_kill);
+  super::reg_json_async(rt, "op_command_output", op_command_output);
+  super::reg_json_sync(rt, "op_command_output_sync", op_command_output_sync);
+  super::reg_json_sync(rt, "op_run_pty", op_run_pty);
 }
 
 fn clone_file(
@@ -54,10 +66,26 @@ fn subprocess_stdio_map(s: &str) -> Result<std::process::Stdio, AnyError> {
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct RunArgs {
+struct CommandArgs {
   cmd: Vec<String>,
   cwd: Option<String>,
   env: Vec<(String, String)>,
+  /// Don't inherit the environment variables of the parent process.
+  #[serde(default)]
+  clear_env: bool,
+  uid: Option<u32>,
+  gid: Option<u32>,
+  /// Start the child in a new process group, so it can be killed together
+  /// with its descendants.
+  #[serde(default)]
+  process_group: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RunArgs {
+  #[serde(flatten)]
+  command: CommandArgs,
   stdin: String,
   stdout: String,
   stderr: String,
@@ -66,6 +94,16 @@ struct RunArgs {
   stderr_rid: u32,
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RunPtyArgs {
+  #[serde(flatten)]
+  command: CommandArgs,
+  cols: u16,
+  rows: u16,
+  raw: bool,
+}
+
 struct ChildResource {
   child: AsyncRefCell<tokio::process::Child>,
 }
@@ -82,28 +120,70 @@ impl ChildResource {
   }
 }
 
-fn op_run(
+/// Builds the command described by `args`, leaving its stdio to the caller.
+fn create_command(
   state: &mut OpState,
-  args: Value,
-  _zero_copy: &mut [ZeroCopyBuf],
-) -> Result<Value, AnyError> {
-  let run_args: RunArgs = serde_json::from_value(args)?;
+  args: &CommandArgs,
+) -> Result<std::process::Command, AnyError> {
   state.borrow::<Permissions>().check_run()?;
+  if args.clear_env
+    || args.uid.is_some()
+    || args.gid.is_some()
+    || args.process_group
+  {
+    super::check_unstable(state, "Deno.Command");
+  }
 
-  let args = run_args.cmd;
-  let env = run_args.env;
-  let cwd = run_args.cwd;
-
-  let mut c = Command::new(args.get(0).unwrap());
-  (1..args.len()).for_each(|i| {
-    let arg = args.get(i).unwrap();
-    c.arg(arg);
-  });
-  cwd.map(|d| c.current_dir(d));
-  for (key, value) in &env {
+  let cmd = &args.cmd;
+  let mut c = std::process::Command::new(cmd.get(0).unwrap());
+  c.args(&cmd[1..]);
+  if let Some(cwd) = &args.cwd {
+    c.current_dir(cwd);
+  }
+  if args.clear_env {
+    c.env_clear();
+  }
+  for (key, value) in &args.env {
     c.env(key, value);
   }
 
+  #[cfg(unix)]
+  {
+    if let Some(uid) = args.uid {
+      c.uid(uid);
+    }
+    if let Some(gid) = args.gid {
+      c.gid(gid);
+    }
+    if args.process_group {
+      // SAFETY: setpgid is async-signal-safe, so it can be called between
+      // fork and exec.
+      unsafe {
+        c.pre_exec(|| {
+          use nix::unistd::{setpgid, Pid};
+          setpgid(Pid::from_raw(0), Pid::from_raw(0))
+            .map_err(|_| std::io::Error::last_os_error())
+        });
+      }
+    }
+  }
+  #[cfg(not(unix))]
+  {
+    if args.uid.is_some() || args.gid.is_some() {
+      return Err(not_supported());
+    }
+  }
+
+  Ok(c)
+}
+
+/// Builds the command described by `run_args`, with its stdio configured.
+fn create_run_command(
+  state: &mut OpState,
+  run_args: &RunArgs,
+) -> Result<std::process::Command, AnyError> {
+  let mut c = create_command(state, &run_args.command)?;
+
   // TODO: make this work with other resources, eg. sockets
   if !run_args.stdin.is_empty() {
     c.stdin(subprocess_stdio_map(run_args.stdin.as_ref())?);
@@ -126,6 +206,17 @@ fn op_run(
     c.stderr(file);
   }
 
+  Ok(c)
+}
+
+fn op_run(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  let run_args: RunArgs = serde_json::from_value(args)?;
+  let mut c = Command::from(create_run_command(state, &run_args)?);
+
   // We want to kill child when it's closed
   c.kill_on_drop(true);
 
@@ -177,6 +268,87 @@ fn op_run(
   }))
 }
 
+/// Spawns the command with a new pseudo-terminal as its stdio. The master side
+/// of the terminal is returned as a file resource, so the tty ops work on it.
+#[cfg(unix)]
+fn op_run_pty(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  use nix::fcntl::{fcntl, FcntlArg, FdFlag};
+  use nix::pty::{openpty, Winsize};
+  use nix::sys::termios::Termios;
+  use std::os::unix::io::FromRawFd;
+
+  super::check_unstable(state, "Deno.runPty");
+  let args: RunPtyArgs = serde_json::from_value(args)?;
+  let mut c = create_command(state, &args.command)?;
+
+  let winsize = Winsize {
+    ws_row: args.rows,
+    ws_col: args.cols,
+    ws_xpixel: 0,
+    ws_ypixel: 0,
+  };
+  let pty = openpty(Some(&winsize), None::<&Termios>)?;
+  let master = unsafe { std::fs::File::from_raw_fd(pty.master) };
+  let slave = unsafe { std::fs::File::from_raw_fd(pty.slave) };
+  // Neither end should leak into the child beyond its stdio.
+  fcntl(pty.master, FcntlArg::F_SETFD(FdFlag::FD_CLOEXEC))?;
+  fcntl(pty.slave, FcntlArg::F_SETFD(FdFlag::FD_CLOEXEC))?;
+
+  c.stdin(slave.try_clone()?);
+  c.stdout(slave.try_clone()?);
+  c.stderr(slave);
+  // SAFETY: setsid and ioctl are async-signal-safe, so they can be called
+  // between fork and exec.
+  unsafe {
+    c.pre_exec(|| {
+      // Start a new session with the terminal as its controlling terminal, so
+      // the child gets job control and terminal generated signals.
+      if libc::setsid() == -1 || libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1 {
+        return Err(std::io::Error::last_os_error());
+      }
+      Ok(())
+    });
+  }
+
+  let mut c = Command::from(c);
+  c.kill_on_drop(true);
+  let child = c.spawn()?;
+  let pid = child.id();
+  // Close the slave side in this process, so reads from the master end once
+  // the child and its descendants have exited.
+  drop(c);
+
+  let pty_rid = state
+    .resource_table
+    .add(StdFileResource::pty(tokio::fs::File::from_std(master)));
+  if args.raw {
+    super::tty::set_raw_mode(state, pty_rid, true, false)?;
+  }
+  let child_rid = state.resource_table.add(ChildResource {
+    child: AsyncRefCell::new(child),
+  });
+
+  Ok(json!({
+    "rid": child_rid,
+    "pid": pid,
+    "ptyRid": pty_rid,
+  }))
+}
+
+#[cfg(not(unix))]
+fn op_run_pty(
+  state: &mut OpState,
+  _args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  super::check_unstable(state, "Deno.runPty");
+  Err(not_supported())
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct RunStatusArgs {
@@ -203,6 +375,10 @@ async fn op_run_status(
     .ok_or_else(bad_resource_id)?;
   let mut child = resource.borrow_mut().await;
   let run_status = child.wait().await?;
+  Ok(exit_status_json(run_status))
+}
+
+fn exit_status_json(run_status: ExitStatus) -> Value {
   let code = run_status.code();
 
   #[cfg(unix)]
@@ -215,11 +391,102 @@ async fn op_run_status(
     .expect("Should have either an exit code or a signal.");
   let got_signal = signal.is_some();
 
-  Ok(json!({
+  json!({
      "gotSignal": got_signal,
      "exitCode": code.unwrap_or(-1),
      "exitSignal": signal.unwrap_or(-1),
-  }))
+  })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CommandOutputArgs {
+  rid: u32,
+  stdout_rid: Option<u32>,
+  stderr_rid: Option<u32>,
+}
+
+async fn read_to_end<S>(
+  resource: Option<Rc<ReadOnlyResource<S>>>,
+) -> Result<Vec<u8>, AnyError>
+where
+  S: AsyncRead + Unpin + 'static,
+{
+  let mut buf = Vec::new();
+  if let Some(resource) = resource {
+    let mut rd = resource.borrow_mut().await;
+    rd.read_to_end(&mut buf)
+      .try_or_cancel(resource.cancel_handle())
+      .await?;
+  }
+  Ok(buf)
+}
+
+/// Waits for the child spawned by `op_run` to exit while collecting its
+/// piped stdout and stderr, which are returned base64 encoded.
+async fn op_command_output(
+  state: Rc<RefCell<OpState>>,
+  args: Value,
+  _zero_copy: BufVec,
+) -> Result<Value, AnyError> {
+  let args: CommandOutputArgs = serde_json::from_value(args)?;
+
+  let (resource, stdout, stderr) = {
+    let state = state.borrow();
+    super::check_unstable(&state, "Deno.Command");
+    state.borrow::<Permissions>().check_run()?;
+    let resource = state
+      .resource_table
+      .get::<ChildResource>(args.rid)
+      .ok_or_else(bad_resource_id)?;
+    let stdout = match args.stdout_rid {
+      Some(rid) => Some(
+        state
+          .resource_table
+          .get::<ChildStdoutResource>(rid)
+          .ok_or_else(bad_resource_id)?,
+      ),
+      None => None,
+    };
+    let stderr = match args.stderr_rid {
+      Some(rid) => Some(
+        state
+          .resource_table
+          .get::<ChildStderrResource>(rid)
+          .ok_or_else(bad_resource_id)?,
+      ),
+      None => None,
+    };
+    (resource, stdout, stderr)
+  };
+
+  let mut child = resource.borrow_mut().await;
+  let (run_status, stdout, stderr) = tokio::try_join!(
+    async { child.wait().await.map_err(AnyError::from) },
+    read_to_end(stdout),
+    read_to_end(stderr),
+  )?;
+
+  let mut output = exit_status_json(run_status);
+  output["stdout"] = json!(base64::encode(stdout));
+  output["stderr"] = json!(base64::encode(stderr));
+  Ok(output)
+}
+
+/// Runs a command to completion, blocking until it exits.
+fn op_command_output_sync(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  super::check_unstable(state, "Deno.Command");
+  let run_args: RunArgs = serde_json::from_value(args)?;
+  let output = create_run_command(state, &run_args)?.output()?;
+
+  let mut result = exit_status_json(output.status);
+  result["stdout"] = json!(base64::encode(output.stdout));
+  result["stderr"] = json!(base64::encode(output.stderr));
+  Ok(result)
 }
 
 #[cfg(unix)]
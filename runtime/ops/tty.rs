@@ -49,6 +49,7 @@ pub fn init(rt: &mut deno_core::JsRuntime) {
   super::reg_json_sync(rt, "op_set_raw", op_set_raw);
   super::reg_json_sync(rt, "op_isatty", op_isatty);
   super::reg_json_sync(rt, "op_console_size", op_console_size);
+  super::reg_json_sync(rt, "op_pty_resize", op_pty_resize);
 }
 
 #[derive(Deserialize)]
@@ -152,67 +153,81 @@ fn op_set_raw(
   }
   #[cfg(unix)]
   {
-    use std::os::unix::io::AsRawFd;
+    set_raw_mode(state, rid, is_raw, cbreak)?;
+    Ok(json!({}))
+  }
+}
 
-    let resource = state
-      .resource_table
-      .get::<StdFileResource>(rid)
-      .ok_or_else(bad_resource_id)?;
+/// Puts the terminal of a file resource into raw mode, saving its original
+/// mode in the resource, or restores the saved mode. This is used by
+/// `Deno.setRaw` and for the pseudo-terminals of `Deno.runPty`.
+#[cfg(unix)]
+pub fn set_raw_mode(
+  state: &mut OpState,
+  rid: u32,
+  is_raw: bool,
+  cbreak: bool,
+) -> Result<(), AnyError> {
+  use std::os::unix::io::AsRawFd;
 
-    if resource.fs_file.is_none() {
-      return Err(not_supported());
-    }
+  let resource = state
+    .resource_table
+    .get::<StdFileResource>(rid)
+    .ok_or_else(bad_resource_id)?;
 
-    let maybe_fs_file_resource =
-      RcRef::map(&resource, |r| r.fs_file.as_ref().unwrap()).try_borrow_mut();
+  if resource.fs_file.is_none() {
+    return Err(not_supported());
+  }
 
-    if maybe_fs_file_resource.is_none() {
-      return Err(resource_unavailable());
-    }
+  let maybe_fs_file_resource =
+    RcRef::map(&resource, |r| r.fs_file.as_ref().unwrap()).try_borrow_mut();
 
-    let mut fs_file_resource = maybe_fs_file_resource.unwrap();
-    if fs_file_resource.0.is_none() {
-      return Err(resource_unavailable());
-    }
+  if maybe_fs_file_resource.is_none() {
+    return Err(resource_unavailable());
+  }
 
-    let raw_fd = fs_file_resource.0.as_ref().unwrap().as_raw_fd();
-    let maybe_tty_mode = &mut fs_file_resource.1.as_mut().unwrap().tty.mode;
+  let mut fs_file_resource = maybe_fs_file_resource.unwrap();
+  if fs_file_resource.0.is_none() {
+    return Err(resource_unavailable());
+  }
 
-    if is_raw {
-      if maybe_tty_mode.is_none() {
-        // Save original mode.
-        let original_mode = termios::tcgetattr(raw_fd)?;
-        maybe_tty_mode.replace(original_mode);
-      }
+  let raw_fd = fs_file_resource.0.as_ref().unwrap().as_raw_fd();
+  let maybe_tty_mode = &mut fs_file_resource.1.as_mut().unwrap().tty.mode;
 
-      let mut raw = maybe_tty_mode.clone().unwrap();
+  if is_raw {
+    if maybe_tty_mode.is_none() {
+      // Save original mode.
+      let original_mode = termios::tcgetattr(raw_fd)?;
+      maybe_tty_mode.replace(original_mode);
+    }
 
-      raw.input_flags &= !(termios::InputFlags::BRKINT
-        | termios::InputFlags::ICRNL
-        | termios::InputFlags::INPCK
-        | termios::InputFlags::ISTRIP
-        | termios::InputFlags::IXON);
+    let mut raw = maybe_tty_mode.clone().unwrap();
 
-      raw.control_flags |= termios::ControlFlags::CS8;
+    raw.input_flags &= !(termios::InputFlags::BRKINT
+      | termios::InputFlags::ICRNL
+      | termios::InputFlags::INPCK
+      | termios::InputFlags::ISTRIP
+      | termios::InputFlags::IXON);
 
-      raw.local_flags &= !(termios::LocalFlags::ECHO
-        | termios::LocalFlags::ICANON
-        | termios::LocalFlags::IEXTEN);
-      if !cbreak {
-        raw.local_flags &= !(termios::LocalFlags::ISIG);
-      }
-      raw.control_chars[termios::SpecialCharacterIndices::VMIN as usize] = 1;
-      raw.control_chars[termios::SpecialCharacterIndices::VTIME as usize] = 0;
-      termios::tcsetattr(raw_fd, termios::SetArg::TCSADRAIN, &raw)?;
-    } else {
-      // Try restore saved mode.
-      if let Some(mode) = maybe_tty_mode.take() {
-        termios::tcsetattr(raw_fd, termios::SetArg::TCSADRAIN, &mode)?;
-      }
-    }
+    raw.control_flags |= termios::ControlFlags::CS8;
 
-    Ok(json!({}))
+    raw.local_flags &= !(termios::LocalFlags::ECHO
+      | termios::LocalFlags::ICANON
+      | termios::LocalFlags::IEXTEN);
+    if !cbreak {
+      raw.local_flags &= !(termios::LocalFlags::ISIG);
+    }
+    raw.control_chars[termios::SpecialCharacterIndices::VMIN as usize] = 1;
+    raw.control_chars[termios::SpecialCharacterIndices::VTIME as usize] = 0;
+    termios::tcsetattr(raw_fd, termios::SetArg::TCSADRAIN, &raw)?;
+  } else {
+    // Try restore saved mode.
+    if let Some(mode) = maybe_tty_mode.take() {
+      termios::tcsetattr(raw_fd, termios::SetArg::TCSADRAIN, &mode)?;
+    }
   }
+
+  Ok(())
 }
 
 #[derive(Deserialize)]
@@ -323,3 +338,53 @@ fn op_console_size(
 
   Ok(json!(size))
 }
+
+#[derive(Deserialize)]
+struct PtyResizeArgs {
+  rid: u32,
+  cols: u16,
+  rows: u16,
+}
+
+fn op_pty_resize(
+  state: &mut OpState,
+  args: Value,
+  _zero_copy: &mut [ZeroCopyBuf],
+) -> Result<Value, AnyError> {
+  super::check_unstable(state, "Deno.PtyProcess.resize");
+
+  let args: PtyResizeArgs = serde_json::from_value(args)?;
+
+  StdFileResource::with(state, args.rid, move |r| match r {
+    Ok(std_file) => {
+      #[cfg(windows)]
+      {
+        let _ = std_file;
+        Err(not_supported())
+      }
+
+      #[cfg(unix)]
+      {
+        use std::os::unix::io::AsRawFd;
+
+        let fd = std_file.as_raw_fd();
+        let size = libc::winsize {
+          ws_row: args.rows,
+          ws_col: args.cols,
+          ws_xpixel: 0,
+          ws_ypixel: 0,
+        };
+        // The kernel sends SIGWINCH to the foreground process group of the
+        // terminal when its size changes.
+        if unsafe { libc::ioctl(fd, libc::TIOCSWINSZ, &size as *const _) } != 0
+        {
+          return Err(Error::last_os_error().into());
+        }
+        Ok(())
+      }
+    }
+    Err(_) => Err(bad_resource_id()),
+  })?;
+
+  Ok(json!({}))
+}
@@ -139,6 +139,10 @@ pub struct WebWorkerOptions {
   pub debug_flag: bool,
   pub unstable: bool,
   pub ca_data: Option<Vec<u8>>,
+  /// Certificate stores to take the root certificates for `fetch` from.
+  pub ca_stores: Option<Vec<String>>,
+  /// Proxy to route `fetch` requests through.
+  pub proxy: Option<deno_fetch::Proxy>,
   pub user_agent: String,
   pub seed: Option<u64>,
   pub module_loader: Rc<dyn ModuleLoader>,
@@ -221,7 +225,9 @@ impl WebWorker {
       ops::fetch::init(
         js_runtime,
         options.user_agent.clone(),
+        options.ca_stores.clone(),
         options.ca_data.clone(),
+        options.proxy.clone(),
       );
       ops::timers::init(js_runtime);
       ops::worker_host::init(
@@ -516,6 +522,8 @@ mod tests {
       debug_flag: false,
       unstable: false,
       ca_data: None,
+      ca_stores: None,
+      proxy: None,
       user_agent: "x".to_string(),
       seed: None,
       module_loader,
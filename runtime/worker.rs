@@ -5,6 +5,7 @@ use crate::inspector::InspectorServer;
 use crate::inspector::InspectorSession;
 use crate::js;
 use crate::metrics::RuntimeMetrics;
+use crate::op_crates;
 use crate::ops;
 use crate::permissions::Permissions;
 use deno_core::error::AnyError;
@@ -21,6 +22,7 @@ use deno_core::ModuleId;
 use deno_core::ModuleLoader;
 use deno_core::ModuleSpecifier;
 use deno_core::RuntimeOptions;
+use deno_core::Snapshot;
 use std::env;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -38,6 +40,7 @@ pub struct MainWorker {
   inspector: Option<Box<DenoInspector>>,
   pub js_runtime: JsRuntime,
   should_break_on_first_statement: bool,
+  will_snapshot: bool,
 }
 
 pub struct WorkerOptions {
@@ -47,6 +50,10 @@ pub struct WorkerOptions {
   pub debug_flag: bool,
   pub unstable: bool,
   pub ca_data: Option<Vec<u8>>,
+  /// Certificate stores to take the root certificates for `fetch` from.
+  pub ca_stores: Option<Vec<String>>,
+  /// Proxy to route `fetch` requests through.
+  pub proxy: Option<deno_fetch::Proxy>,
   pub user_agent: String,
   pub seed: Option<u64>,
   pub module_loader: Rc<dyn ModuleLoader>,
@@ -72,13 +79,81 @@ impl MainWorker {
     main_module: ModuleSpecifier,
     permissions: Permissions,
     options: &WorkerOptions,
+  ) -> Self {
+    Self::from_runtime_options(
+      main_module,
+      permissions,
+      options,
+      RuntimeOptions {
+        startup_snapshot: Some(js::deno_isolate_init()),
+        ..Default::default()
+      },
+    )
+  }
+
+  /// Create a worker whose runtime can be snapshotted with
+  /// `JsRuntime::snapshot()` after evaluating user code. The runtime
+  /// JavaScript is executed from source, as a runtime which will be
+  /// snapshotted can't start from a snapshot. Async ops can't complete in
+  /// this worker.
+  pub fn for_snapshot(
+    main_module: ModuleSpecifier,
+    permissions: Permissions,
+    options: &WorkerOptions,
+  ) -> Self {
+    let mut worker = Self::from_runtime_options(
+      main_module,
+      permissions,
+      options,
+      RuntimeOptions {
+        will_snapshot: true,
+        ..Default::default()
+      },
+    );
+    worker.will_snapshot = true;
+
+    let js_runtime = &mut worker.js_runtime;
+    op_crates::init(js_runtime);
+    for (name, source) in js::RUNTIME_JS_FILES {
+      js_runtime
+        .execute(name, source)
+        .expect("Failed to execute runtime JavaScript");
+    }
+
+    worker
+  }
+
+  /// Create a worker from a snapshot taken of a worker created with
+  /// `MainWorker::for_snapshot()`. The worker has to be started with
+  /// `MainWorker::restore()` instead of `MainWorker::bootstrap()`.
+  pub fn from_snapshot(
+    main_module: ModuleSpecifier,
+    permissions: Permissions,
+    options: &WorkerOptions,
+    snapshot: Snapshot,
+  ) -> Self {
+    Self::from_runtime_options(
+      main_module,
+      permissions,
+      options,
+      RuntimeOptions {
+        startup_snapshot: Some(snapshot),
+        ..Default::default()
+      },
+    )
+  }
+
+  fn from_runtime_options(
+    main_module: ModuleSpecifier,
+    permissions: Permissions,
+    options: &WorkerOptions,
+    runtime_options: RuntimeOptions,
   ) -> Self {
     let mut js_runtime = JsRuntime::new(RuntimeOptions {
       module_loader: Some(options.module_loader.clone()),
-      startup_snapshot: Some(js::deno_isolate_init()),
       js_error_create_fn: options.js_error_create_fn.clone(),
       get_error_class_fn: options.get_error_class_fn,
-      ..Default::default()
+      ..runtime_options
     });
 
     let inspector = if options.attach_inspector {
@@ -97,6 +172,7 @@ impl MainWorker {
       inspector,
       js_runtime,
       should_break_on_first_statement,
+      will_snapshot: false,
     };
 
     let js_runtime = &mut worker.js_runtime;
@@ -116,7 +192,9 @@ impl MainWorker {
       ops::fetch::init(
         js_runtime,
         options.user_agent.clone(),
+        options.ca_stores.clone(),
         options.ca_data.clone(),
+        options.proxy.clone(),
       );
       ops::timers::init(js_runtime);
       ops::worker_host::init(
@@ -175,8 +253,8 @@ impl MainWorker {
     worker
   }
 
-  pub fn bootstrap(&mut self, options: &WorkerOptions) {
-    let runtime_options = json!({
+  fn runtime_options(&self, options: &WorkerOptions) -> serde_json::Value {
+    json!({
       "args": options.args,
       "applySourceMaps": options.apply_source_maps,
       "debugFlag": options.debug_flag,
@@ -189,17 +267,33 @@ impl MainWorker {
       "unstableFlag": options.unstable,
       "v8Version": deno_core::v8_version(),
       "location": options.location,
-    });
+      "willSnapshot": self.will_snapshot,
+    })
+  }
 
+  pub fn bootstrap(&mut self, options: &WorkerOptions) {
     let script = format!(
       "bootstrap.mainRuntime({})",
-      serde_json::to_string_pretty(&runtime_options).unwrap()
+      serde_json::to_string_pretty(&self.runtime_options(options)).unwrap()
     );
     self
       .execute(&script)
       .expect("Failed to execute bootstrap script");
   }
 
+  /// Restart a worker created with `MainWorker::from_snapshot()`. Only the
+  /// options which may differ from the time the snapshot was taken, such as
+  /// `Deno.args`, are applied.
+  pub fn restore(&mut self, options: &WorkerOptions) {
+    let script = format!(
+      "bootstrap.restoreMainRuntime({})",
+      serde_json::to_string_pretty(&self.runtime_options(options)).unwrap()
+    );
+    self
+      .execute(&script)
+      .expect("Failed to execute restore script");
+  }
+
   /// Same as execute2() but the filename defaults to "$CWD/__anonymous__".
   pub fn execute(&mut self, js_source: &str) -> Result<(), AnyError> {
     let path = env::current_dir().unwrap().join("__anonymous__");
@@ -284,17 +378,16 @@ mod tests {
   use super::*;
   use deno_core::resolve_url_or_path;
 
-  fn create_test_worker() -> MainWorker {
-    let main_module = resolve_url_or_path("./hello.js").unwrap();
-    let permissions = Permissions::default();
-
-    let options = WorkerOptions {
+  fn create_test_options(args: Vec<String>) -> WorkerOptions {
+    WorkerOptions {
       apply_source_maps: false,
       user_agent: "x".to_string(),
-      args: vec![],
+      args,
       debug_flag: false,
       unstable: false,
       ca_data: None,
+      ca_stores: None,
+      proxy: None,
       seed: None,
       js_error_create_fn: None,
       create_web_worker_cb: Arc::new(|_| unreachable!()),
@@ -307,11 +400,52 @@ mod tests {
       no_color: true,
       get_error_class_fn: None,
       location: None,
-    };
+    }
+  }
+
+  fn create_test_worker() -> MainWorker {
+    let main_module = resolve_url_or_path("./hello.js").unwrap();
+    let permissions = Permissions::default();
+    let options = create_test_options(vec![]);
 
     MainWorker::from_options(main_module, permissions, &options)
   }
 
+  #[test]
+  fn restore_from_snapshot() {
+    let main_module = resolve_url_or_path("./hello.js").unwrap();
+    let options = create_test_options(vec!["build".to_string()]);
+    let mut worker = MainWorker::for_snapshot(
+      main_module.clone(),
+      Permissions::default(),
+      &options,
+    );
+    worker.bootstrap(&options);
+    worker
+      .execute("globalThis.buildArgs = Deno.args.join();")
+      .unwrap();
+    let snapshot = worker.js_runtime.snapshot();
+    let snapshot = Snapshot::Boxed(snapshot.to_vec().into_boxed_slice());
+
+    let options = create_test_options(vec!["run".to_string()]);
+    let mut worker = MainWorker::from_snapshot(
+      main_module,
+      Permissions::default(),
+      &options,
+      snapshot,
+    );
+    worker.restore(&options);
+    worker
+      .execute(
+        r#"
+      if (buildArgs !== "build" || Deno.args.join() !== "run") {
+        throw Error("bad");
+      }
+    "#,
+      )
+      .unwrap();
+  }
+
   #[tokio::test]
   async fn execute_mod_esm_imports_a() {
     let p = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))